@@ -1,27 +1,50 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
 use async_trait::async_trait;
 use futures::StreamExt;
-use log::info;
+use log::{error, info};
 use ratatui::{
     buffer::Buffer,
-    crossterm::event::{self, Event, EventStream, KeyCode, KeyEvent, KeyEventKind},
+    crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
     layout::{Constraint, Layout, Rect},
     prelude::*,
     style::{Modifier, Style, Stylize},
     symbols::border::{self},
     text::{Line, Text},
     widgets::{
-        block::{Position, Title}, Block, Gauge, Padding, Paragraph, StatefulWidgetRef, Tabs, Widget, Wrap
+        block::{Position, Title}, Block, Gauge, Padding, Paragraph, RenderDirection, Sparkline, StatefulWidgetRef, TableState, Tabs, Widget, Wrap
     },
     DefaultTerminal,
 };
 
-use shared::error::{AppError, AppResult};
+use shared::{error::AppResult, util::DisplayBytes};
 use strum::IntoEnumIterator;
-use tui_scrollview::{ScrollView, ScrollViewState};
+use sysinfo::Pid;
+use tui_scrollview::ScrollViewState;
 
 use crate::{
-    system::{prelude::*, SysData, SysInfo},
-    widget::{ProcessTab, SelectedTab},
+    system::{
+        history::{History, HistoryWindow, SystemHistory},
+        prelude::*,
+        diskstats::DiskIo,
+        frequency::CoreFrequency,
+        pressure::{PressureResource, PressureSnapshot},
+        process::{
+            event::{diff_snapshots, oom_events, EventKind, ExitCause},
+            memory::MemoryCache,
+            signal::ProcessSignal,
+            ProcessSnapshot,
+        },
+        SysData, SysInfo,
+    },
+    widget::{
+        pressure_avgs, CpuBreakdownBar, CpuFrequencyGrid, CpuGrid, CpuView, DiskGauges, DiskIoTab,
+        EventTab, MemoryBar, Popup, PortTab, ProcessTab, ProcessTarget, SelectedTab, SensorTab,
+        StatusMessage,
+    },
 };
 
 macro_rules! title_block {
@@ -39,14 +62,7 @@ macro_rules! title_block {
 
 macro_rules! line {
     ($($arg:expr),*) => {
-        {
-            let mut temp = vec![];
-            $(
-                temp.push($arg);
-            )*
-
-            Line::from(temp)
-        }
+        Line::from(vec![$($arg),*])
     };
 }
 
@@ -57,23 +73,35 @@ pub struct Tui {
     state: AppState,
     selected_tab: SelectedTab,
     is_clear: bool,
-    scrollview_state: ScrollViewState,
-    //process_view: ProcessTab,
+    process_view: ProcessTab,
+    process_state: TableState,
+    process_snapshot: ProcessSnapshot,
+    input_mode: InputMode,
+    popup: Option<Popup>,
+    message: Option<StatusMessage>,
+    events: EventTab,
+    event_state: TableState,
+    /// 送ったシグナルを終了理由に使うため、終了を見つけるまで覚えておく
+    signaled: HashMap<Pid, ProcessSignal>,
+    ports: PortTab,
+    port_state: TableState,
+    /// 全プロセスのfdを見るので表示中だけ間隔を空けて読む
+    ports_refreshed_at: Option<Instant>,
+    sensors: SensorTab,
+    sensor_state: TableState,
+    disk_io: DiskIoTab,
+    disk_io_state: TableState,
+    cpu_view: CpuView,
+    history: SystemHistory,
+    history_window: HistoryWindow,
+    /// PSS/USS/SWAPの列に出す値
+    memory_cache: MemoryCache,
+    /// 直前の更新で読んだPSI。まだ読んでいなければ`None`
+    pressure: Option<AppResult<PressureSnapshot>>,
+    /// コアごとの周波数。周波数の表示中だけ更新で読む
+    frequencies: Vec<CoreFrequency>,
 }
 
-// impl Tui {
-//     pub fn new(_terminal: DefaultTerminal) -> Self {
-//         Self {
-//             sysinfos: SysInfo::default(),
-//             sysdata: SysData::default(),
-//             state: AppState::default(),
-//             selected_tab: SelectedTab::default(),
-//             is_clear: bool::default(),
-//             scrollview_state: ScrollViewState::default(),
-//         }
-//     }
-// }
-
 #[derive(Default, PartialEq, Eq)]
 pub enum AppState {
     #[default]
@@ -81,6 +109,14 @@ pub enum AppState {
     QUIT,
 }
 
+/// 文字入力中は通常のキー操作を止める
+#[derive(Default, PartialEq, Eq)]
+pub enum InputMode {
+    #[default]
+    Normal,
+    Filter,
+}
+
 #[async_trait]
 pub trait Application {
     async fn run(mut self, terminal: &mut DefaultTerminal) -> AppResult<()>;
@@ -126,6 +162,17 @@ impl Application for Tui {
     }
 
     fn handle_key_event(&mut self, key_event: &KeyEvent) {
+        if let Some(popup) = self.popup.take() {
+            self.handle_popup_key_event(popup, key_event);
+            return;
+        }
+        self.message = None;
+
+        if self.input_mode == InputMode::Filter {
+            self.handle_filter_key_event(key_event);
+            return;
+        }
+
         match key_event.code {
             KeyCode::Char('q') => self.exit(),
             KeyCode::Char('r') => self.refresh(),
@@ -137,17 +184,85 @@ impl Application for Tui {
                 self.is_clear = true;
                 self.next_tab();
             }
-            KeyCode::Char('G') if self.selected_tab == SelectedTab::Process => {
-                self.scrollview_state.scroll_to_bottom()
+            KeyCode::Char('G' | 'g')
+            | KeyCode::Up
+            | KeyCode::Down
+            | KeyCode::PageUp
+            | KeyCode::PageDown
+                if self.selected_tab != SelectedTab::Main =>
+            {
+                self.navigate(key_event.code)
+            }
+            KeyCode::Char('s') if self.selected_tab == SelectedTab::Process => {
+                self.process_view.next_sort_column();
+                self.reload_process_view();
+            }
+            KeyCode::Char('o') if self.selected_tab == SelectedTab::Process => {
+                self.process_view.sort.toggle_order();
+                self.reload_process_view();
+            }
+            KeyCode::Char('t') if self.selected_tab == SelectedTab::Process => {
+                self.process_view.toggle_mode();
+                self.reload_process_view();
             }
-            KeyCode::Char('g') if self.selected_tab == SelectedTab::Process => {
-                self.scrollview_state.scroll_to_top()
+            KeyCode::Char('m') if self.selected_tab == SelectedTab::Process => {
+                self.process_view.toggle_memory();
+                self.reload_process_view();
             }
-            KeyCode::Up if self.selected_tab == SelectedTab::Process => {
-                self.scrollview_state.scroll_up()
+            KeyCode::Char('a') if self.selected_tab == SelectedTab::Process => {
+                self.process_view.cycle_group();
+                self.reload_process_view();
             }
-            KeyCode::Down if self.selected_tab == SelectedTab::Process => {
-                self.scrollview_state.scroll_down()
+            KeyCode::Char(' ') if self.selected_tab == SelectedTab::Process => {
+                self.process_view.toggle_collapsed(&self.process_state);
+                self.reload_process_view();
+            }
+            KeyCode::Enter if self.selected_tab == SelectedTab::Process => self.open_detail(),
+            KeyCode::Char('k') if self.selected_tab == SelectedTab::Process => {
+                if let Some(row) = self.process_view.selected(&self.process_state) {
+                    self.popup = Some(Popup::SignalMenu {
+                        target: ProcessTarget::from(row),
+                        selected: 0,
+                    });
+                }
+            }
+            KeyCode::Char('/') if self.selected_tab == SelectedTab::Process => {
+                self.input_mode = InputMode::Filter;
+            }
+            KeyCode::Esc if self.selected_tab == SelectedTab::Process => {
+                self.process_view.filter.clear();
+                self.reload_process_view();
+            }
+            KeyCode::Char('c') if self.selected_tab == SelectedTab::Main => {
+                self.cpu_view = self.cpu_view.next();
+                self.reload_frequencies();
+            }
+            KeyCode::Char('w')
+                if matches!(self.selected_tab, SelectedTab::Main | SelectedTab::DiskIo) =>
+            {
+                self.history_window = self.history_window.next();
+            }
+            KeyCode::Char('i') if self.selected_tab == SelectedTab::Main => {
+                match self.sysinfos.get_meminfo() {
+                    Ok(info) => self.popup = Some(Popup::Memory(Box::new(info))),
+                    Err(e) => {
+                        error!("{e}");
+                        self.message = Some(StatusMessage::Error(format!(
+                            "Failed to read memory details: {e}"
+                        )));
+                    }
+                }
+            }
+            KeyCode::Char('p') if self.selected_tab == SelectedTab::Main => {
+                self.popup = Some(Popup::Swap(Box::new(self.sysinfos.get_swap_summary())));
+            }
+            KeyCode::Char('l') if self.selected_tab == SelectedTab::Ports => {
+                self.ports.show_all = !self.ports.show_all;
+                self.ports_refreshed_at = None;
+            }
+            KeyCode::Char('x') if self.selected_tab == SelectedTab::Ports => {
+                self.ports.show_unix = !self.ports.show_unix;
+                self.ports_refreshed_at = None;
             }
             _ => {}
         }
@@ -159,6 +274,283 @@ impl Application for Tui {
         }
 
         self.sysinfos.refresh_all();
+        // 描画の度に読み直さないよう、この更新で読んだ値を使い回す
+        let disk_io = self.sysinfos.get_disk_io();
+        self.pressure = Some(self.sysinfos.get_pressure());
+        self.record_history(&disk_io);
+        let snapshot = self.sysinfos.get_process_snapshot();
+        self.record_events(&snapshot);
+        self.process_snapshot = snapshot;
+        self.reload_process_view();
+        self.reload_ports();
+        self.reload_sensors();
+        self.reload_frequencies();
+        self.disk_io.set_devices(disk_io, &mut self.disk_io_state);
+        self.reload_popup();
+    }
+}
+
+impl Tui {
+    fn handle_filter_key_event(&mut self, key_event: &KeyEvent) {
+        let filter = &mut self.process_view.filter;
+        match key_event.code {
+            KeyCode::Char('r') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                filter.toggle_regex()
+            }
+            KeyCode::Char('t') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                filter.toggle_ignore_case()
+            }
+            KeyCode::Char(c) => filter.push(c),
+            KeyCode::Backspace => filter.pop(),
+            KeyCode::Enter => self.input_mode = InputMode::Normal,
+            KeyCode::Esc => {
+                filter.clear();
+                self.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+        self.reload_process_view();
+    }
+
+    /// 閉じない場合は次のポップアップを`self.popup`に戻す
+    fn handle_popup_key_event(&mut self, popup: Popup, key_event: &KeyEvent) {
+        self.popup = match (popup, key_event.code) {
+            (_, KeyCode::Esc) => None,
+            (Popup::SignalMenu { target, selected }, KeyCode::Up) => Some(Popup::SignalMenu {
+                target,
+                selected: selected.saturating_sub(1),
+            }),
+            (Popup::SignalMenu { target, selected }, KeyCode::Down) => Some(Popup::SignalMenu {
+                target,
+                selected: (selected + 1).min(Popup::SIGNAL_MENU_LEN - 1),
+            }),
+            (Popup::SignalMenu { target, selected }, KeyCode::Enter) => {
+                match ProcessSignal::MENU.get(selected) {
+                    Some(signal) => Some(Popup::ConfirmSignal {
+                        target,
+                        signal: *signal,
+                    }),
+                    None => Some(Popup::SignalNumber {
+                        target,
+                        input: String::new(),
+                    }),
+                }
+            }
+            (Popup::SignalNumber { target, mut input }, KeyCode::Char(c)) => {
+                input.push(c);
+                Some(Popup::SignalNumber { target, input })
+            }
+            (Popup::SignalNumber { target, mut input }, KeyCode::Backspace) => {
+                input.pop();
+                Some(Popup::SignalNumber { target, input })
+            }
+            (Popup::SignalNumber { target, input }, KeyCode::Enter) => {
+                match ProcessSignal::parse(&input) {
+                    Some(signal) => Some(Popup::ConfirmSignal { target, signal }),
+                    None => {
+                        self.message =
+                            Some(StatusMessage::Error(format!("Unknown signal: {input}")));
+                        Some(Popup::SignalNumber { target, input })
+                    }
+                }
+            }
+            (Popup::ConfirmSignal { target, signal }, KeyCode::Char('y' | 'Y') | KeyCode::Enter) => {
+                self.send_signal(target, signal);
+                None
+            }
+            (Popup::ConfirmSignal { .. }, KeyCode::Char('n' | 'N')) => None,
+            (Popup::Detail { .. }, KeyCode::Char('q')) => None,
+            (Popup::Memory(_), KeyCode::Char('q' | 'i')) => None,
+            (Popup::Swap(_), KeyCode::Char('q' | 'p')) => None,
+            (Popup::Detail { target, detail, mut scroll }, code) => {
+                match code {
+                    KeyCode::Up => scroll.scroll_up(),
+                    KeyCode::Down => scroll.scroll_down(),
+                    KeyCode::PageUp => scroll.scroll_page_up(),
+                    KeyCode::PageDown => scroll.scroll_page_down(),
+                    KeyCode::Char('g') => scroll.scroll_to_top(),
+                    KeyCode::Char('G') => scroll.scroll_to_bottom(),
+                    _ => {}
+                }
+                Some(Popup::Detail { target, detail, scroll })
+            }
+            (popup, _) => Some(popup),
+        };
+    }
+
+    fn open_detail(&mut self) {
+        let Some(row) = self.process_view.selected(&self.process_state) else {
+            return;
+        };
+        let target = ProcessTarget::from(row);
+        match self.sysinfos.get_process_detail(target.pid) {
+            Ok(detail) => {
+                self.popup = Some(Popup::Detail {
+                    target,
+                    detail: Box::new(detail),
+                    scroll: ScrollViewState::default(),
+                })
+            }
+            Err(e) => {
+                error!("{e}");
+                self.message = Some(StatusMessage::Error(format!(
+                    "Failed to read PID {}: {e}",
+                    target.pid
+                )));
+            }
+        }
+    }
+
+    fn send_signal(&mut self, target: ProcessTarget, signal: ProcessSignal) {
+        self.message = Some(match self.sysinfos.send_signal(target.pid, signal) {
+            Ok(()) => {
+                let message = format!("Sent {signal} to {} ({})", target.name, target.pid);
+                info!("{message}");
+                self.signaled.insert(target.pid, signal);
+                StatusMessage::Info(message)
+            }
+            Err(e) => {
+                error!("{e}");
+                StatusMessage::Error(e.to_string())
+            }
+        });
+    }
+
+    fn record_history(&mut self, disk_io: &[DiskIo]) {
+        let now = Instant::now();
+        let (received, transmitted) = self.sysinfos.get_network_rates();
+        let history = &mut self.history;
+        history.cpu.push(now, self.sysinfos.get_cpu() as f64);
+        history.memory.push(now, self.sysinfos.get_memory());
+        history.swap.push(now, self.sysinfos.get_swap());
+        history.received.push(now, received as f64);
+        history.transmitted.push(now, transmitted as f64);
+        // PSIがない環境では何も積まない
+        if let Some(Ok(pressure)) = &self.pressure {
+            for resource in PressureResource::iter() {
+                history
+                    .pressure_mut(resource)
+                    .push(now, pressure.get(resource).some.avg10);
+            }
+        }
+        history.push_disks(now, disk_io);
+    }
+
+    fn record_events(&mut self, snapshot: &ProcessSnapshot) {
+        let mut events = diff_snapshots(&self.process_snapshot, snapshot);
+        for event in events.iter_mut() {
+            if event.kind == EventKind::Exited {
+                if let Some(signal) = self.signaled.remove(&event.pid) {
+                    event.cause = Some(ExitCause::Signaled(signal));
+                }
+            }
+            info!("{event}");
+        }
+        self.signaled.retain(|pid, _| snapshot.get(pid).is_some());
+
+        // どのタブにいても気付けるよう、OOM killは状態欄にも出す
+        let kills = self.sysinfos.get_oom_kills();
+        let oom_events = oom_events(&self.process_snapshot, snapshot, &mut events, &kills);
+        for event in oom_events.iter() {
+            error!("{event}");
+            self.message = Some(StatusMessage::Error(event.to_string()));
+        }
+        events.extend(oom_events);
+        self.events.push_events(events, &mut self.event_state);
+    }
+
+    fn reload_ports(&mut self) {
+        if self.selected_tab != SelectedTab::Ports
+            || self
+                .ports_refreshed_at
+                .is_some_and(|at| at.elapsed() < Self::PORTS_INTERVAL)
+        {
+            return;
+        }
+        self.ports
+            .set_sockets(self.sysinfos.get_sockets(), &mut self.port_state);
+        self.ports_refreshed_at = Some(Instant::now());
+    }
+
+    fn reload_sensors(&mut self) {
+        if self.selected_tab != SelectedTab::Sensors {
+            return;
+        }
+        self.sysinfos.refresh_sensors();
+        self.sensors
+            .set_sensors(self.sysinfos.get_sensors(), &mut self.sensor_state);
+    }
+
+    fn reload_frequencies(&mut self) {
+        if self.selected_tab == SelectedTab::Main && self.cpu_view == CpuView::Frequency {
+            self.frequencies = self.sysinfos.get_frequencies();
+        } else {
+            self.frequencies.clear();
+        }
+    }
+
+    /// 読めなかった時は前回の値のまま出しておく
+    fn reload_popup(&mut self) {
+        match &mut self.popup {
+            Some(Popup::Memory(info)) => {
+                if let Ok(latest) = self.sysinfos.get_meminfo() {
+                    **info = latest;
+                }
+            }
+            Some(Popup::Swap(summary)) => **summary = self.sysinfos.get_swap_summary(),
+            _ => {}
+        }
+    }
+
+    fn navigate(&mut self, code: KeyCode) {
+        let state = match self.selected_tab {
+            SelectedTab::Main => return,
+            SelectedTab::Process => &mut self.process_state,
+            SelectedTab::Events => &mut self.event_state,
+            SelectedTab::Ports => &mut self.port_state,
+            SelectedTab::Sensors => &mut self.sensor_state,
+            SelectedTab::DiskIo => &mut self.disk_io_state,
+        };
+        match code {
+            KeyCode::Char('G') => state.select_last(),
+            KeyCode::Char('g') => state.select_first(),
+            KeyCode::Up => state.select_previous(),
+            KeyCode::Down => state.select_next(),
+            KeyCode::PageUp => state.scroll_up_by(Self::PAGE_SIZE),
+            KeyCode::PageDown => state.scroll_down_by(Self::PAGE_SIZE),
+            _ => {}
+        }
+    }
+
+    fn reload_process_view(&mut self) {
+        let mut rows = self.process_snapshot.rows();
+        // 並べ替えに使う値は絞り込みで残る行だけ、少しずつ読んで覚えておく
+        if self.process_view.sorts_by_memory() {
+            let filter = &self.process_view.filter;
+            let (mut shown, hidden): (Vec<_>, Vec<_>) =
+                rows.into_iter().partition(|row| filter.matches(row));
+            let sysinfos = &self.sysinfos;
+            self.memory_cache.fill(&mut shown, Instant::now(), |pid| {
+                sysinfos.get_process_memory(pid)
+            });
+            shown.extend(hidden);
+            rows = shown;
+        }
+        self.process_view.set_rows(rows, &mut self.process_state);
+
+        // smaps_rollupは重いので見えている行だけ、並べ替えと同じく覚えた値を使って読む
+        if self.process_view.show_memory && !self.process_view.sorts_by_memory() {
+            let mut visible = self.process_view.visible_rows(&self.process_state);
+            let sysinfos = &self.sysinfos;
+            self.memory_cache.fill(&mut visible, Instant::now(), |pid| {
+                sysinfos.get_process_memory(pid)
+            });
+            let memory = visible
+                .into_iter()
+                .filter_map(|row| Some((row.pid, row.memory?)))
+                .collect();
+            self.process_view.set_memory(memory);
+        }
     }
 }
 
@@ -170,6 +562,15 @@ impl Tui {
             .title(title.alignment(ratatui::layout::Alignment::Center))
             .border_set(border::THICK);
 
+        let load = self.sysinfos.get_load_average();
+        let (running, blocked) = self.sysinfos.get_run_queue();
+        // 1分平均がコア数を超えたら実行待ちが出ている
+        let load_color = if load.one > self.sysinfos.get_cpus().len() as f64 {
+            Color::Yellow
+        } else {
+            Color::Green
+        };
+
         let os_info = Text::from(vec![
             line!("OS: ".into(), self.sysdata.get_os_long_ver().green()),
             line!("HOST: ".into(), self.sysdata.get_host().green()),
@@ -195,7 +596,23 @@ impl Tui {
             ),
             line!(
                 "Total Swap: ".into(),
-                self.sysinfos.get_total_swap().to_string().green()
+                DisplayBytes::new(self.sysinfos.get_total_swap()).to_string().green()
+            ),
+            line!(
+                "Load Average: ".into(),
+                format!("{:.2} {:.2} {:.2}", load.one, load.five, load.fifteen).fg(load_color)
+            ),
+            line!(
+                "Tasks: ".into(),
+                format!("{running} running, {blocked} blocked").green()
+            ),
+            line!(
+                "Context Switches: ".into(),
+                format!("{}/s", self.sysinfos.get_context_switch_rate()).green()
+            ),
+            line!(
+                "Interrupts: ".into(),
+                format!("{}/s", self.sysinfos.get_interrupt_rate()).green()
             ),
         ]);
 
@@ -205,47 +622,273 @@ impl Tui {
             .render(area, buf);
     }
 
+    /// 資源ごとにsomeとfullのavg10/60/300を並べ、右にsome avg10の推移を出す
+    fn render_pressure_info(&self, area: Rect, buf: &mut Buffer) {
+        const TEXT_WIDTH: u16 = 48;
+        let block = Block::bordered()
+            .title(
+                Title::from(format!(" Pressure {} ", self.history_window).blue().bold())
+                    .alignment(Alignment::Center),
+            )
+            .border_set(border::THICK);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let pressure = match &self.pressure {
+            Some(Ok(pressure)) => pressure,
+            None => return,
+            Some(Err(err)) => {
+                Paragraph::new(err.to_string().dark_gray())
+                    .wrap(Wrap { trim: true })
+                    .render(inner, buf);
+                return;
+            }
+        };
+        let rows = Layout::vertical([Constraint::Length(1); 4]).split(inner);
+        Line::from(format!("{:<7}{:>19} │{:>19}", "", "some 10/60/300", "full 10/60/300").bold())
+            .render(rows[0], buf);
+        for (resource, row) in PressureResource::iter().zip(rows.iter().skip(1)) {
+            let [text_area, history_area] =
+                Layout::horizontal([Constraint::Length(TEXT_WIDTH), Constraint::Fill(1)])
+                    .areas(*row);
+            let value = pressure.get(resource);
+            // 少しでも全タスクが止まっていれば目立たせる
+            let color = match value.full.map_or(value.some.avg10, |full| full.avg10) {
+                v if v >= 10. => Color::Red,
+                v if v > 0. => Color::Yellow,
+                _ => Color::Green,
+            };
+            Line::from(vec![
+                format!("{:<7}", resource.to_string()).into(),
+                pressure_avgs(Some(&value.some)).fg(color),
+                " │".into(),
+                pressure_avgs(value.full.as_ref()).fg(color),
+            ])
+            .render(text_area, buf);
+            let data = self.history_data(self.history.pressure(resource), 10., history_area.width);
+            Sparkline::default()
+                .data(&data)
+                .max(1000)
+                .direction(RenderDirection::RightToLeft)
+                .style(Style::default().fg(Color::Cyan))
+                .render(history_area, buf);
+        }
+    }
+
     fn render_disk_info(&self, area: Rect, buf: &mut Buffer) {
-        let disk_info = Text::from(
-            self.sysinfos
-                .get_disks_info()
-                .into_iter()
-                .map(Line::from)
-                .collect::<Vec<Line<'_>>>(),
-        );
+        let disks = self.sysinfos.get_disks_info();
         let block = Block::bordered()
-            .title(Title::from(Line::from(" Disk Info ".red().bold())))
+            .title(Title::from(Line::from(
+                format!(" Disks ({}) ", disks.len()).red().bold(),
+            )))
             .border_set(border::THICK);
 
-        Paragraph::new(disk_info)
-            .left_aligned()
-            .block(block)
-            .render(area, buf);
+        DiskGauges::new(&disks).render(block.inner(area), buf);
+        block.render(area, buf);
+    }
+
+    /// 選択中のデバイスの読み書きと利用率
+    fn render_disk_io_history(&self, area: Rect, buf: &mut Buffer) {
+        let Some(name) = self
+            .disk_io
+            .selected(&self.disk_io_state)
+            .map(|device| &device.name)
+        else {
+            return;
+        };
+        let Some(history) = self.history.disks.get(name) else {
+            return;
+        };
+        let [read_area, write_area, util_area] =
+            Layout::horizontal([Constraint::Fill(1); 3]).areas(area);
+        let rate = |history: &History| {
+            DisplayBytes::new(history.latest().unwrap_or_default() as u64).to_string()
+        };
+        self.render_history(
+            &history.read,
+            format!(" {name} Read {}/s ({}) ", rate(&history.read), self.history_window),
+            None,
+            read_area,
+            buf,
+        );
+        self.render_history(
+            &history.write,
+            format!(" {name} Write {}/s ({}) ", rate(&history.write), self.history_window),
+            None,
+            write_area,
+            buf,
+        );
+        self.render_history(
+            &history.util,
+            format!(
+                " {name} Util {:.0}% ({}) ",
+                history.util.latest().unwrap_or_default(),
+                self.history_window
+            ),
+            Some(100.),
+            util_area,
+            buf,
+        );
     }
 
     fn draw_bottom(&self, area: Rect, buf: &mut Buffer) {
-        let instructions = Title::from(Line::from(vec![
+        let mut keys = vec![
             " Quit ".into(),
             "<Q>".red().bold(),
             " Refresh ".into(),
             "<R>".red().bold(),
-        ]));
+        ];
+        if self.input_mode == InputMode::Filter {
+            keys = vec![
+                " Apply ".into(),
+                "<Enter>".red().bold(),
+                " Cancel ".into(),
+                "<Esc>".red().bold(),
+                " Regex ".into(),
+                "<^R>".red().bold(),
+                " Ignore Case ".into(),
+                "<^T>".red().bold(),
+            ];
+        } else if self.selected_tab == SelectedTab::Process {
+            keys.extend([
+                " Sort ".into(),
+                "<S>".red().bold(),
+                " Order ".into(),
+                "<O>".red().bold(),
+                " Filter ".into(),
+                "</>".red().bold(),
+                " Tree ".into(),
+                "<T>".red().bold(),
+                " Group ".into(),
+                "<A>".red().bold(),
+                " Memory ".into(),
+                "<M>".red().bold(),
+                " Fold ".into(),
+                "<Space>".red().bold(),
+                " Signal ".into(),
+                "<K>".red().bold(),
+                " Detail ".into(),
+                "<Enter>".red().bold(),
+            ]);
+        } else if self.selected_tab == SelectedTab::Main {
+            keys.extend([
+                " CPU View ".into(),
+                "<C>".red().bold(),
+                " History Window ".into(),
+                "<W>".red().bold(),
+                " Memory Detail ".into(),
+                "<I>".red().bold(),
+                " Swap Detail ".into(),
+                "<P>".red().bold(),
+            ]);
+        } else if self.selected_tab == SelectedTab::Ports {
+            keys.extend([
+                " All/Listening ".into(),
+                "<L>".red().bold(),
+                " Unix ".into(),
+                "<X>".red().bold(),
+            ]);
+        } else if matches!(
+            self.selected_tab,
+            SelectedTab::Events | SelectedTab::Sensors | SelectedTab::DiskIo
+        ) {
+            keys.extend([
+                " Top ".into(),
+                "<g>".red().bold(),
+                " Bottom ".into(),
+                "<G>".red().bold(),
+            ]);
+            if self.selected_tab == SelectedTab::DiskIo {
+                keys.extend([" History Window ".into(), "<W>".red().bold()]);
+            }
+        }
+        let instructions = Title::from(Line::from(keys));
 
-        Block::bordered()
+        let mut block = Block::bordered()
             .title(
                 instructions
                     .alignment(ratatui::layout::Alignment::Center)
                     .position(Position::Bottom),
             )
+            .border_set(border::EMPTY);
+        if let Some(message) = &self.message {
+            block = block.title(
+                Title::from(message.line())
+                    .alignment(ratatui::layout::Alignment::Left)
+                    .position(Position::Top),
+            );
+        }
+        block.render(area, buf);
+    }
+
+    /// ゲージの右にグラフを置く
+    fn split_history(area: Rect) -> [Rect; 2] {
+        Layout::horizontal([Constraint::Fill(3), Constraint::Fill(2)]).areas(area)
+    }
+
+    /// 新しい順に並べ、`scale`倍して整数にする
+    fn history_data(&self, history: &History, scale: f64, width: u16) -> Vec<u64> {
+        history
+            .downsample(self.history_window, width as usize)
+            .iter()
+            .rev()
+            .map(|value| (value * scale).round() as u64)
+            .collect()
+    }
+
+    /// `max`がなければ期間中の最大値に合わせる
+    fn render_history(
+        &self,
+        history: &History,
+        title: String,
+        max: Option<f64>,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let block = Block::bordered()
             .border_set(border::EMPTY)
-            .render(area, buf);
+            .title(Title::from(title.blue().bold()).alignment(Alignment::Center));
+        // Sparklineは整数しか受け付けないので割合は0.1%単位にする
+        let scale = if max.is_some() { 10. } else { 1. };
+        let data = self.history_data(history, scale, block.inner(area).width);
+
+        let mut sparkline = Sparkline::default()
+            .block(block)
+            .data(&data)
+            .direction(RenderDirection::RightToLeft)
+            .style(Style::default().fg(ratatui::style::Color::Cyan));
+        if let Some(max) = max {
+            sparkline = sparkline.max((max * scale) as u64);
+        }
+        sparkline.render(area, buf);
     }
 
     fn draw_mem_info(&self, area: Rect, buf: &mut Buffer) {
+        let [area, history_area] = Self::split_history(area);
+        self.render_history(
+            &self.history.memory,
+            format!(" Memory {} ", self.history_window),
+            Some(100.),
+            history_area,
+            buf,
+        );
         let mem = self.sysinfos.get_memory();
 
+        // キャッシュの分を見分けられるよう、余裕があれば内訳の棒をゲージの下に出す
+        let block = title_block!(" Memory Usage ", 0);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        let gauge_area = match self.sysinfos.get_meminfo() {
+            Ok(info) if inner.height >= 3 => {
+                let [gauge_area, breakdown_area] =
+                    Layout::vertical([Constraint::Fill(1), Constraint::Length(2)]).areas(inner);
+                MemoryBar::new(&info).render(breakdown_area, buf);
+                gauge_area
+            }
+            _ => inner,
+        };
+
         Gauge::default()
-            .block(title_block!(" Memory Usage ", 1))
             .gauge_style(
                 Style::default()
                     .fg(ratatui::style::Color::Blue)
@@ -255,14 +898,50 @@ impl Tui {
             .ratio(mem / 100.)
             .use_unicode(true)
             .label(format!("{:.2}%", mem))
-            .render(area, buf);
+            .render(gauge_area, buf);
     }
 
     fn render_swap_info(&self, area: Rect, buf: &mut Buffer) {
+        let [area, history_area] = Self::split_history(area);
+        self.render_history(
+            &self.history.swap,
+            format!(" Swap {} ", self.history_window),
+            Some(100.),
+            history_area,
+            buf,
+        );
         let swap = self.sysinfos.get_swap();
 
+        // 使用量より出入りの速さの方がスラッシングに気付きやすい
+        let block = title_block!(" Swap Usage ", 0);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        let gauge_area = if inner.height >= 2 {
+            let [gauge_area, rate_area] =
+                Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+            let (swap_in, swap_out) = self.sysinfos.get_swap_rates();
+            let rate = |bytes: u64| {
+                let text = format!("{}/s", DisplayBytes::new(bytes));
+                if bytes > 0 {
+                    text.red().bold()
+                } else {
+                    text.green()
+                }
+            };
+            Line::from(vec![
+                "in ".into(),
+                rate(swap_in),
+                "  out ".into(),
+                rate(swap_out),
+            ])
+            .centered()
+            .render(rate_area, buf);
+            gauge_area
+        } else {
+            inner
+        };
+
         Gauge::default()
-            .block(title_block!(" Swap Usage ", 1))
             .gauge_style(
                 Style::default()
                     .fg(ratatui::style::Color::Blue)
@@ -272,14 +951,51 @@ impl Tui {
             .ratio(swap / 100.)
             .use_unicode(true)
             .label(format!("{:.2}%", swap))
-            .render(area, buf);
+            .render(gauge_area, buf);
     }
 
     fn render_cpu_info(&self, area: Rect, buf: &mut Buffer) {
+        let [area, history_area] = Self::split_history(area);
+        self.render_history(
+            &self.history.cpu,
+            format!(" CPU {} ", self.history_window),
+            Some(100.),
+            history_area,
+            buf,
+        );
+        if self.cpu_view == CpuView::Frequency {
+            let block = title_block!(" CPU Frequency ", 0);
+            CpuFrequencyGrid::new(&self.frequencies).render(block.inner(area), buf);
+            block.render(area, buf);
+            return;
+        }
+        if self.cpu_view == CpuView::PerCore {
+            let cpus = self.sysinfos.get_cpus();
+            let breakdowns = self.sysinfos.get_cpu_breakdowns();
+            let block = title_block!(format!(" CPU Usage ({} cores) ", cpus.len()), 0);
+            CpuGrid::new(&cpus)
+                .breakdowns(&breakdowns)
+                .render(block.inner(area), buf);
+            block.render(area, buf);
+            return;
+        }
         let cpu = self.sysinfos.get_cpu() as f64;
 
+        // 内訳の棒と凡例を入れる余裕がある時だけゲージの下に出す
+        let block = title_block!(" CPU Usage ", 0);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        let gauge_area = match self.sysinfos.get_cpu_breakdown() {
+            Some(breakdown) if inner.height >= 3 => {
+                let [gauge_area, breakdown_area] =
+                    Layout::vertical([Constraint::Fill(1), Constraint::Length(2)]).areas(inner);
+                CpuBreakdownBar::new(&breakdown).render(breakdown_area, buf);
+                gauge_area
+            }
+            _ => inner,
+        };
+
         Gauge::default()
-            .block(title_block!(" CPU Usage "))
             .gauge_style(
                 Style::default()
                     .fg(ratatui::style::Color::Blue)
@@ -288,7 +1004,7 @@ impl Tui {
             .ratio(cpu / 100.)
             .use_unicode(true)
             .label(format!("{:.2}%", cpu))
-            .render(area, buf);
+            .render(gauge_area, buf);
     }
 
     fn render_tabs(&self, area: Rect, buf: &mut Buffer) {
@@ -305,7 +1021,7 @@ impl Tui {
         let test = self.sysinfos.get_networks_info();
         let line = Text::from(
             test.iter()
-                .map(|n| {
+                .flat_map(|n| {
                     let mut temp = vec![Line::from(n.get_name().as_str().green().bold())];
                     temp.push(line!(
                         "Mac Address:".into(),
@@ -317,51 +1033,39 @@ impl Tui {
                     }
                     temp
                 })
-                .flatten()
                 .collect::<Vec<Line<'_>>>(),
         );
 
-        Paragraph::new(line)
-            .block(
-                Block::bordered()
-                    .border_set(border::THICK)
-                    .title(" Network ")
-                    .title_alignment(Alignment::Center),
-            )
-            .render(area, buf);
-    }
-
-    fn render_processes_scrollview(&self, buf: &mut Buffer){
-        let area = buf.area;
-
-        let [header, main] =
-            Layout::vertical([Constraint::Percentage(7), Constraint::Percentage(93)]).areas(area);
-
-        let [_numbers, widgets] =
-            Layout::horizontal([Constraint::Length(5), Constraint::Fill(1)]).areas(main);
-        let header_text = Text::from(Line::from(vec!["PID".into(), "\t".into(), "NAME".into()]));
-
-        Paragraph::new(header_text)
-            .block(Block::bordered().border_set(border::THICK))
-            .render(header, buf);
+        let block = Block::bordered()
+            .border_set(border::THICK)
+            .title(" Network ")
+            .title_alignment(Alignment::Center);
+        let [info_area, rates_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(4)]).areas(block.inner(area));
+        let [received_area, transmitted_area] =
+            Layout::horizontal([Constraint::Fill(1); 2]).areas(rates_area);
+        block.render(area, buf);
 
-        let line = Text::from(
-            self.sysinfos
-                .get_processes()
+        Paragraph::new(line).render(info_area, buf);
+        let rate = |history: &History| {
+            DisplayBytes::new(history.latest().unwrap_or_default() as u64).to_string()
+        };
+        self.render_history(
+            &self.history.received,
+            format!(" RX {}/s ", rate(&self.history.received)),
+            None,
+            received_area,
+            buf,
+        );
+        self.render_history(
+            &self.history.transmitted,
+            format!(" TX {}/s ", rate(&self.history.transmitted)),
+            None,
+            transmitted_area,
+            buf,
         );
-
-        Paragraph::new(line)
-            .block(
-                Block::bordered()
-                    .border_set(border::THICK)
-                    .title(" Process ")
-                    .title_alignment(Alignment::Center),
-            )
-            .wrap(Wrap { trim: false })     
-            .render(widgets,buf);
     }
-
-}       
+}
 
 //汎用
 impl Tui {
@@ -380,6 +1084,9 @@ impl Tui {
 }
 
 impl Tui {
+    const PAGE_SIZE: u16 = 20;
+    const PORTS_INTERVAL: Duration = Duration::from_secs(1);
+
     fn next_tab(&mut self) {
         self.selected_tab = self.selected_tab.next();
     }
@@ -407,9 +1114,12 @@ impl Widget for &mut Tui {
                 let [mem_gauge_area, cpu_gauge_area, swap_gauge_area, disk_info_area] =
                     Layout::vertical([Constraint::Ratio(1, 4); 4]).areas(left);
 
-                let [os_info_area, network_info_area] =
-                    Layout::vertical([Constraint::Percentage(70), Constraint::Percentage(30)])
-                        .areas(right);
+                let [os_info_area, pressure_area, network_info_area] = Layout::vertical([
+                    Constraint::Fill(1),
+                    Constraint::Length(6),
+                    Constraint::Percentage(30),
+                ])
+                .areas(right);
 
                 self.render_tabs(tab_footer, buf);
                 self.draw_mem_info(mem_gauge_area, buf);
@@ -417,14 +1127,82 @@ impl Widget for &mut Tui {
                 self.render_swap_info(swap_gauge_area, buf);
                 self.render_disk_info(disk_info_area, buf);
                 self.draw_os_info(os_info_area, buf);
+                self.render_pressure_info(pressure_area, buf);
                 self.render_network_info(network_info_area, buf);
                 self.draw_bottom(bottom, buf);
             }
             SelectedTab::Process => {
-                let mut scrollview = ScrollView::new((area.width - 2, 80).into());
-                self.render_processes_scrollview(scrollview.buf_mut());
-                scrollview.render(area, buf, &mut self.scrollview_state);
+                let editing = self.input_mode == InputMode::Filter;
+                let filter_height = u16::from(editing || self.process_view.filter.is_active());
+                let [tab_footer, filter, main, bottom] = Layout::vertical([
+                    Constraint::Length(1),
+                    Constraint::Length(filter_height),
+                    Constraint::Fill(1),
+                    Constraint::Length(2),
+                ])
+                .areas(area);
+
+                self.render_tabs(tab_footer, buf);
+                self.process_view.set_page_height(main.height);
+                self.process_view.render_filter_bar(filter, buf, editing);
+                self.process_view
+                    .render_ref(main, buf, &mut self.process_state);
+                self.draw_bottom(bottom, buf);
+            }
+            SelectedTab::Events => {
+                let [tab_footer, main, bottom] = Layout::vertical([
+                    Constraint::Length(1),
+                    Constraint::Fill(1),
+                    Constraint::Length(2),
+                ])
+                .areas(area);
+
+                self.render_tabs(tab_footer, buf);
+                self.events.render_ref(main, buf, &mut self.event_state);
+                self.draw_bottom(bottom, buf);
+            }
+            SelectedTab::Ports => {
+                let [tab_footer, main, bottom] = Layout::vertical([
+                    Constraint::Length(1),
+                    Constraint::Fill(1),
+                    Constraint::Length(2),
+                ])
+                .areas(area);
+
+                self.render_tabs(tab_footer, buf);
+                self.ports.render_ref(main, buf, &mut self.port_state);
+                self.draw_bottom(bottom, buf);
             }
+            SelectedTab::Sensors => {
+                let [tab_footer, main, bottom] = Layout::vertical([
+                    Constraint::Length(1),
+                    Constraint::Fill(1),
+                    Constraint::Length(2),
+                ])
+                .areas(area);
+
+                self.render_tabs(tab_footer, buf);
+                self.sensors.render_ref(main, buf, &mut self.sensor_state);
+                self.draw_bottom(bottom, buf);
+            }
+            SelectedTab::DiskIo => {
+                let [tab_footer, main, charts, bottom] = Layout::vertical([
+                    Constraint::Length(1),
+                    Constraint::Fill(1),
+                    Constraint::Length(8),
+                    Constraint::Length(2),
+                ])
+                .areas(area);
+
+                self.render_tabs(tab_footer, buf);
+                self.disk_io.render_ref(main, buf, &mut self.disk_io_state);
+                self.render_disk_io_history(charts, buf);
+                self.draw_bottom(bottom, buf);
+            }
+        }
+
+        if let Some(popup) = &mut self.popup {
+            popup.render(area, buf);
         }
     }
 }
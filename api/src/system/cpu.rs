@@ -1,7 +1,13 @@
-use super::SysInfo;
+use super::{stat::CpuBreakdown, SysInfo};
 pub trait Cpu {
     fn refresh_cpu(&mut self,);
     fn get_cpu(&self)->f32;
+    /// コアごとの使用率
+    fn get_cpus(&self)->Vec<f32>;
+    /// /proc/statの差分から出したCPU時間の内訳。初回の更新前は`None`
+    fn get_cpu_breakdown(&self)->Option<CpuBreakdown>;
+    /// コアごとの内訳。`get_cpus`と同じ順
+    fn get_cpu_breakdowns(&self)->Vec<CpuBreakdown>;
 
 }
 
@@ -16,6 +22,21 @@ impl Cpu for SysInfo {
     fn get_cpu(&self) -> f32 {
         self.system.global_cpu_usage()
     }
+
+    fn get_cpus(&self) -> Vec<f32> {
+        self.system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect()
+    }
+
+    fn get_cpu_breakdown(&self) -> Option<CpuBreakdown> {
+        self.stat.as_ref()?.breakdown(self.prev_stat.as_ref()?)
+    }
+
+    fn get_cpu_breakdowns(&self) -> Vec<CpuBreakdown> {
+        match (&self.stat, &self.prev_stat) {
+            (Some(stat), Some(prev)) => stat.core_breakdowns(prev),
+            _ => vec![],
+        }
+    }
 }
 
 #[cfg(test)]
@@ -30,4 +51,29 @@ mod test{
         assert_ne!(0.,sysinfo.get_cpu());
         Ok(())
     }
+
+    #[test]
+    fn test_get_cpus()->AppResult<()>{
+        let sysinfo= SysInfo::new();
+        let cpus = sysinfo.get_cpus();
+        assert!(!cpus.is_empty());
+        assert!(cpus.iter().all(|usage| (0. ..=100.).contains(usage)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_cpu_breakdown()->AppResult<()>{
+        let mut sysinfo= SysInfo::new();
+        assert_eq!(None,sysinfo.get_cpu_breakdown());
+
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        sysinfo.refresh_all();
+        let breakdowns = sysinfo.get_cpu_breakdowns();
+        assert_eq!(sysinfo.get_cpus().len(),breakdowns.len());
+        if let Some(breakdown) = sysinfo.get_cpu_breakdown() {
+            let total = breakdown.idle + breakdown.segments().iter().map(|(_, value)| value).sum::<f64>();
+            assert!((total - 100.).abs() < 0.01);
+        }
+        Ok(())
+    }
 }
\ No newline at end of file
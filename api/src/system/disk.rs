@@ -1,32 +1,109 @@
-use sysinfo::Disks;
+use std::{
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use strum::Display;
+use sysinfo::DiskKind;
 use shared::util::DisplayOsStr;
-use super::SysInfo;
+use super::{
+    diskstats::{block_devices, DiskIo},
+    SysInfo, SYS_ROOT,
+};
+
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum DiskType {
+    #[strum(to_string = "SSD")]
+    Ssd,
+    #[strum(to_string = "HDD")]
+    Hdd,
+    /// tmpfsや仮想ディスクなど
+    #[strum(to_string = "-")]
+    Unknown,
+}
+
+impl From<DiskKind> for DiskType {
+    fn from(kind: DiskKind) -> Self {
+        match kind {
+            DiskKind::SSD => Self::Ssd,
+            DiskKind::HDD => Self::Hdd,
+            DiskKind::Unknown(_) => Self::Unknown,
+        }
+    }
+}
+
+/// マウントごとの容量。サイズはバイト数
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiskInfo {
+    pub device: String,
+    pub mount_point: PathBuf,
+    pub file_system: String,
+    pub kind: DiskType,
+    pub removable: bool,
+    pub total: u64,
+    pub available: u64,
+}
+
+impl DiskInfo {
+    /// 予約領域があるのでavailableを引いた残りを使用中とみなす
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.available)
+    }
+
+    /// 使用率(%)。容量0のマウントは0
+    pub fn usage(&self) -> f64 {
+        if self.total == 0 {
+            0.
+        } else {
+            self.used() as f64 / self.total as f64 * 100.
+        }
+    }
+}
 
 pub trait Disk {
+    /// マウントの増減と空き容量を読み直す
     fn refresh_disks(&mut self);
-    fn get_disks_info(&self) -> Vec<String>;
+    /// マウントポイント順
+    fn get_disks_info(&self) -> Vec<DiskInfo>;
+    /// ディスクごとのI/O。パーティションと一度もI/Oのないデバイスは除く
+    fn get_disk_io(&self) -> Vec<DiskIo>;
 }
 
 impl Disk for SysInfo {
     fn refresh_disks(&mut self) {
-        self.disks = Disks::new_with_refreshed_list();
+        self.disks.refresh_list();
+        self.disks_refreshed_at = Instant::now();
     }
 
-    fn get_disks_info(&self) -> Vec<String> {
-        self.disks
+    fn get_disks_info(&self) -> Vec<DiskInfo> {
+        let mut disks = self
+            .disks
             .list()
             .iter()
-            .map(|d| {
-                let available_usage = d.available_space() / Self::GIB;
-                let total_usage = d.total_space() / Self::GIB;
-                format!(
-                    "{}:{}:{}:{}/{} GB",
-                    DisplayOsStr::new(d.name()),
-                    d.mount_point().display(),
-                    DisplayOsStr::new(d.file_system()),
-                    available_usage,
-                    total_usage,
-                )
+            .map(|d| DiskInfo {
+                device: DisplayOsStr::new(d.name()).to_string(),
+                mount_point: d.mount_point().to_path_buf(),
+                file_system: DisplayOsStr::new(d.file_system()).to_string(),
+                kind: d.kind().into(),
+                removable: d.is_removable(),
+                total: d.total_space(),
+                available: d.available_space(),
+            })
+            .collect::<Vec<_>>();
+        disks.sort_by(|a, b| a.mount_point.cmp(&b.mount_point));
+        disks
+    }
+
+    fn get_disk_io(&self) -> Vec<DiskIo> {
+        // /sysが読めなければパーティションも出す
+        let devices = block_devices(Path::new(SYS_ROOT));
+        self.diskstats
+            .iter()
+            .filter(|stat| stat.reads + stat.writes > 0)
+            .filter(|stat| devices.as_ref().is_none_or(|devices| devices.contains(&stat.name)))
+            .filter_map(|stat| {
+                let prev = self.prev_diskstats.iter().find(|prev| prev.name == stat.name)?;
+                DiskIo::between(prev, stat, self.elapsed)
             })
             .collect()
     }
@@ -34,6 +111,8 @@ impl Disk for SysInfo {
 
 #[cfg(test)]
 mod test{
+    use std::path::PathBuf;
+
     use shared::error::AppResult;
 
     use crate::system::SysInfo;
@@ -44,8 +123,29 @@ mod test{
         let system = SysInfo::new();
         let info=system.get_disks_info();
         info.iter().for_each(|e| {
-            assert_ne!("",e);
+            assert_ne!("",e.device);
+            // 予約領域があるのでused+availableはtotalと一致しない
+            assert!(e.used() <= e.total);
+            assert!((0. ..=100.).contains(&e.usage()));
         });
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_usage() -> AppResult<()> {
+        let disk = |total, available| DiskInfo {
+            device: "tmpfs".into(),
+            mount_point: PathBuf::from("/run"),
+            file_system: "tmpfs".into(),
+            kind: DiskType::Unknown,
+            removable: false,
+            total,
+            available,
+        };
+        // GiB未満のtmpfsでも割合が出る
+        assert_eq!(25., disk(512 * 1024 * 1024, 384 * 1024 * 1024).usage());
+        assert_eq!(0., disk(0, 0).usage());
+        assert_eq!(0, disk(100, 200).used());
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+use std::{collections::HashSet, fs, path::Path, time::Duration};
+
+use shared::error::AppResult;
+
+/// /proc/diskstatsの1行のうち使う値。どれも起動からの累計で、時間はミリ秒
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DiskStat {
+    pub name: String,
+    pub reads: u64,
+    pub sectors_read: u64,
+    pub read_ticks: u64,
+    pub writes: u64,
+    pub sectors_written: u64,
+    pub write_ticks: u64,
+    /// I/Oが1つ以上処理中だった時間
+    pub io_ticks: u64,
+}
+
+impl DiskStat {
+    /// デバイスによらずdiskstatsのセクタは512バイト
+    const SECTOR_SIZE: u64 = 512;
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace().skip(2);
+        let name = fields.next()?.to_owned();
+        let values = fields
+            .take(10)
+            .map(|value| value.parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        if values.len() < 10 {
+            return None;
+        }
+        Some(Self {
+            name,
+            reads: values[0],
+            sectors_read: values[2],
+            read_ticks: values[3],
+            writes: values[4],
+            sectors_written: values[6],
+            write_ticks: values[7],
+            io_ticks: values[9],
+        })
+    }
+}
+
+pub fn parse_diskstats(content: &str) -> Vec<DiskStat> {
+    content.lines().filter_map(DiskStat::parse).collect()
+}
+
+/// テストではfixtureのディレクトリを渡す
+pub fn read_diskstats(proc_root: &Path) -> AppResult<Vec<DiskStat>> {
+    Ok(parse_diskstats(&fs::read_to_string(proc_root.join("diskstats"))?))
+}
+
+/// `<sys_root>/block`にあるディスク全体の名前。パーティションを除くのに使う
+pub fn block_devices(sys_root: &Path) -> Option<HashSet<String>> {
+    let entries = fs::read_dir(sys_root.join("block")).ok()?;
+    Some(
+        entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect(),
+    )
+}
+
+/// 2回の読み取りの差分から出したデバイスごとのI/O
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DiskIo {
+    pub name: String,
+    /// 毎秒のバイト数
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    /// 1回のI/Oにかかった平均時間(ms)。I/Oがなければ0
+    pub await_ms: f64,
+    /// I/Oを処理していた時間の割合(%)
+    pub util: f64,
+}
+
+impl DiskIo {
+    pub fn between(prev: &DiskStat, next: &DiskStat, elapsed: Duration) -> Option<Self> {
+        if elapsed.is_zero() {
+            return None;
+        }
+        let secs = elapsed.as_secs_f64();
+        let delta = |counter: fn(&DiskStat) -> u64| counter(next).saturating_sub(counter(prev));
+        let ios = delta(|stat| stat.reads) + delta(|stat| stat.writes);
+        let ticks = delta(|stat| stat.read_ticks) + delta(|stat| stat.write_ticks);
+        Some(Self {
+            name: next.name.clone(),
+            read_bytes: (delta(|stat| stat.sectors_read) as f64 * DiskStat::SECTOR_SIZE as f64
+                / secs) as u64,
+            write_bytes: (delta(|stat| stat.sectors_written) as f64 * DiskStat::SECTOR_SIZE as f64
+                / secs) as u64,
+            read_iops: delta(|stat| stat.reads) as f64 / secs,
+            write_iops: delta(|stat| stat.writes) as f64 / secs,
+            await_ms: if ios == 0 {
+                0.
+            } else {
+                ticks as f64 / ios as f64
+            },
+            // 更新の間隔がずれると100%を少し超えることがある
+            util: (delta(|stat| stat.io_ticks) as f64 / elapsed.as_millis() as f64 * 100.)
+                .min(100.),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, time::Duration};
+
+    use shared::error::AppResult;
+
+    use crate::system::fixture::TempDir;
+
+    use super::{read_diskstats, DiskIo, DiskStat};
+
+    const DISKSTATS: &str = "\
+   7       0 loop0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+ 259       0 nvme0n1 1000 10 80000 500 2000 20 160000 1500 0 1200 2000 0 0 0 0 0 0
+ 259       1 nvme0n1p1 900 10 72000 450 1900 20 150000 1400 0 1100 1850
+   8       0 sda 1 2 3
+";
+
+    #[test]
+    fn test_read_diskstats() -> AppResult<()> {
+        let root = TempDir::new("diskstats")?;
+        fs::write(root.join("diskstats"), DISKSTATS)?;
+        let stats = read_diskstats(&root);
+        let stats = stats?;
+
+        // 古いカーネルの11列の行は読み、足りない行は飛ばす
+        assert_eq!(
+            vec!["loop0", "nvme0n1", "nvme0n1p1"],
+            stats.iter().map(|stat| stat.name.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            DiskStat {
+                name: "nvme0n1".into(),
+                reads: 1000,
+                sectors_read: 80000,
+                read_ticks: 500,
+                writes: 2000,
+                sectors_written: 160000,
+                write_ticks: 1500,
+                io_ticks: 1200,
+            },
+            stats[1]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_io() -> AppResult<()> {
+        let prev = DiskStat {
+            name: "sda".into(),
+            reads: 100,
+            sectors_read: 1000,
+            read_ticks: 100,
+            writes: 50,
+            sectors_written: 500,
+            write_ticks: 100,
+            io_ticks: 1000,
+        };
+        let next = DiskStat {
+            reads: 300,
+            sectors_read: 1000 + 4096,
+            read_ticks: 500,
+            writes: 250,
+            sectors_written: 500 + 2048,
+            write_ticks: 1700,
+            io_ticks: 1250,
+            ..prev.clone()
+        };
+
+        let io = DiskIo::between(&prev, &next, Duration::from_millis(500)).unwrap_or_default();
+        assert_eq!(4096 * 512 * 2, io.read_bytes);
+        assert_eq!(2048 * 512 * 2, io.write_bytes);
+        assert_eq!(400., io.read_iops);
+        assert_eq!(400., io.write_iops);
+        assert_eq!(5., io.await_ms);
+        assert_eq!(50., io.util);
+
+        let idle = DiskIo::between(&prev, &prev, Duration::from_secs(1)).unwrap_or_default();
+        assert_eq!((0., 0.), (idle.await_ms, idle.util));
+        assert_eq!(None, DiskIo::between(&prev, &next, Duration::ZERO));
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use std::{
+    fs,
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+use shared::error::AppResult;
+
+/// テストで/procや/sysの代わりに読ませる一時ディレクトリ。
+/// assertが失敗しても残らないようにDropで消す
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// 同時に走るテストとぶつからないよう、テストごとに別の名前を渡す
+    pub fn new(name: &str) -> AppResult<Self> {
+        let path = std::env::temp_dir().join(format!("syswatcher-{name}-{}", std::process::id()));
+        fs::create_dir_all(&path)?;
+        Ok(Self(path))
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
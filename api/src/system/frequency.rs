@@ -0,0 +1,147 @@
+use std::{fs, path::Path};
+
+use super::{SysInfo, SYS_ROOT};
+
+/// 周波数はMHz。cpufreqのないVMなどでは`current`以外が`None`になる
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CoreFrequency {
+    /// `cpu<N>`の番号
+    pub core: usize,
+    pub current: u64,
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+    pub governor: Option<String>,
+    /// 起動からの熱による制限の回数。パッケージの値は同じパッケージのコアで共通
+    pub core_throttles: Option<u64>,
+    pub package_throttles: Option<u64>,
+}
+
+/// `<sys_root>/devices/system/cpu/cpu<N>`を番号順に読む。オフラインのコアも含む
+pub fn read_frequencies(sys_root: &Path) -> Vec<CoreFrequency> {
+    let Ok(entries) = fs::read_dir(sys_root.join("devices").join("system").join("cpu")) else {
+        return vec![];
+    };
+    let mut cores = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let number = name.strip_prefix("cpu")?.parse::<usize>().ok()?;
+            Some(read_core(number, &entry.path()))
+        })
+        .collect::<Vec<_>>();
+    cores.sort_by_key(|core| core.core);
+    cores
+}
+
+fn read_core(core: usize, dir: &Path) -> CoreFrequency {
+    let read = |file: &str| {
+        fs::read_to_string(dir.join(file))
+            .ok()
+            .map(|content| content.trim().to_owned())
+    };
+    // cpufreqの値はkHz
+    let mhz = |file: &str| read(file)?.parse::<u64>().ok().map(|khz| khz / 1000);
+    CoreFrequency {
+        core,
+        current: mhz("cpufreq/scaling_cur_freq")
+            .or_else(|| mhz("cpufreq/cpuinfo_cur_freq"))
+            .unwrap_or_default(),
+        min: mhz("cpufreq/scaling_min_freq"),
+        max: mhz("cpufreq/scaling_max_freq"),
+        governor: read("cpufreq/scaling_governor"),
+        core_throttles: read("thermal_throttle/core_throttle_count").and_then(|v| v.parse().ok()),
+        package_throttles: read("thermal_throttle/package_throttle_count")
+            .and_then(|v| v.parse().ok()),
+    }
+}
+
+pub trait SysFrequency {
+    /// コアごとの周波数。`get_cpus`と同じ順
+    fn get_frequencies(&self) -> Vec<CoreFrequency>;
+}
+
+impl SysFrequency for SysInfo {
+    fn get_frequencies(&self) -> Vec<CoreFrequency> {
+        let sysfs = read_frequencies(Path::new(SYS_ROOT));
+        // オフラインのコアがあると番号が詰まるので名前で対応させる
+        self.system
+            .cpus()
+            .iter()
+            .enumerate()
+            .map(|(idx, cpu)| {
+                let number = cpu
+                    .name()
+                    .strip_prefix("cpu")
+                    .and_then(|number| number.parse().ok())
+                    .unwrap_or(idx);
+                let mut core = sysfs
+                    .iter()
+                    .find(|core| core.core == number)
+                    .cloned()
+                    .unwrap_or(CoreFrequency {
+                        core: number,
+                        ..Default::default()
+                    });
+                // cpufreqがなければ/proc/cpuinfoの値を使う
+                if core.current == 0 {
+                    core.current = cpu.frequency();
+                }
+                core
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use shared::error::AppResult;
+
+    use crate::system::fixture::TempDir;
+
+    use super::{read_frequencies, CoreFrequency};
+
+    #[test]
+    fn test_read_frequencies() -> AppResult<()> {
+        let root = TempDir::new("cpufreq")?;
+        let cpu = root.join("devices").join("system").join("cpu");
+        for (core, cur) in [(0, "2400000"), (10, "800000"), (2, "3600000")] {
+            let dir = cpu.join(format!("cpu{core}"));
+            fs::create_dir_all(dir.join("cpufreq"))?;
+            fs::write(dir.join("cpufreq").join("scaling_cur_freq"), cur)?;
+            fs::write(dir.join("cpufreq").join("scaling_min_freq"), "800000\n")?;
+            fs::write(dir.join("cpufreq").join("scaling_max_freq"), "4200000\n")?;
+            fs::write(dir.join("cpufreq").join("scaling_governor"), "powersave\n")?;
+        }
+        fs::create_dir_all(cpu.join("cpu0").join("thermal_throttle"))?;
+        fs::write(cpu.join("cpu0").join("thermal_throttle").join("core_throttle_count"), "12\n")?;
+        fs::write(cpu.join("cpu0").join("thermal_throttle").join("package_throttle_count"), "3\n")?;
+        fs::create_dir_all(cpu.join("cpufreq"))?;
+        fs::create_dir_all(cpu.join("cpuidle"))?;
+        // cpufreqのないコア
+        fs::create_dir_all(cpu.join("cpu11"))?;
+
+        let cores = read_frequencies(&root);
+
+        assert_eq!(
+            vec![2400, 3600, 800, 0],
+            cores.iter().map(|core| core.current).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            CoreFrequency {
+                core: 0,
+                current: 2400,
+                min: Some(800),
+                max: Some(4200),
+                governor: Some("powersave".into()),
+                core_throttles: Some(12),
+                package_throttles: Some(3),
+            },
+            cores[0]
+        );
+        assert_eq!(Some(11), cores.last().map(|core| core.core));
+        assert_eq!(None, cores[1].core_throttles);
+        Ok(())
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use strum::Display;
+
+use super::{diskstats::DiskIo, pressure::PressureResource};
+
+/// グラフに出す期間
+#[derive(Debug, Default, Display, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryWindow {
+    #[default]
+    #[strum(to_string = "60s")]
+    OneMinute,
+    #[strum(to_string = "5m")]
+    FiveMinutes,
+    #[strum(to_string = "1h")]
+    OneHour,
+}
+
+impl HistoryWindow {
+    pub fn duration(self) -> Duration {
+        match self {
+            Self::OneMinute => Duration::from_secs(60),
+            Self::FiveMinutes => Duration::from_secs(5 * 60),
+            Self::OneHour => Duration::from_secs(60 * 60),
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::OneMinute => Self::FiveMinutes,
+            Self::FiveMinutes => Self::OneHour,
+            Self::OneHour => Self::OneMinute,
+        }
+    }
+}
+
+/// 1秒ごとの平均値を最長の期間分だけ持つリングバッファ
+#[derive(Debug)]
+pub struct History {
+    samples: VecDeque<f64>,
+    capacity: usize,
+    /// 集計中の1秒間の開始時刻、合計、件数
+    pending: Option<(Instant, f64, u32)>,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(HistoryWindow::OneHour.duration())
+    }
+}
+
+impl History {
+    const RESOLUTION: Duration = Duration::from_secs(1);
+
+    pub fn new(span: Duration) -> Self {
+        let capacity = (span.as_secs() / Self::RESOLUTION.as_secs()) as usize;
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+            pending: None,
+        }
+    }
+
+    pub fn push(&mut self, now: Instant, value: f64) {
+        // NaNが混ざると平均も最大値も壊れるので捨てる
+        if !value.is_finite() {
+            return;
+        }
+        match self.pending {
+            Some((start, sum, count)) if now.duration_since(start) < Self::RESOLUTION => {
+                self.pending = Some((start, sum + value, count + 1));
+            }
+            Some((_, sum, count)) => {
+                self.flush(sum / count as f64);
+                self.pending = Some((now, value, 1));
+            }
+            None => self.pending = Some((now, value, 1)),
+        }
+    }
+
+    fn flush(&mut self, value: f64) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// 直近`window`の値を古い順に`width`個以下にまとめる。
+    /// 一瞬の山が消えないよう、まとめる時は平均ではなく最大値を取る
+    pub fn downsample(&self, window: HistoryWindow, width: usize) -> Vec<f64> {
+        let count = (window.duration().as_secs() / Self::RESOLUTION.as_secs()) as usize;
+        let recent = self
+            .samples
+            .iter()
+            .skip(self.samples.len().saturating_sub(count))
+            .copied()
+            .collect::<Vec<_>>();
+        if width == 0 || recent.len() <= width {
+            return recent;
+        }
+
+        // 期間全体をwidth個に割り、データのある区間だけを返す
+        let per_bucket = count.div_ceil(width);
+        let missing = count.saturating_sub(recent.len());
+        let mut buckets: Vec<Option<f64>> = vec![None; count.div_ceil(per_bucket)];
+        for (idx, value) in recent.into_iter().enumerate() {
+            let bucket = &mut buckets[(missing + idx) / per_bucket];
+            *bucket = Some(bucket.map_or(value, |max| max.max(value)));
+        }
+        buckets.into_iter().flatten().collect()
+    }
+
+    pub fn latest(&self) -> Option<f64> {
+        self.samples.back().copied()
+    }
+}
+
+/// Mainタブでグラフにする値の履歴
+#[derive(Debug, Default)]
+pub struct SystemHistory {
+    pub cpu: History,
+    pub memory: History,
+    pub swap: History,
+    /// 全インターフェースの毎秒の送受信バイト数
+    pub received: History,
+    pub transmitted: History,
+    /// PSIの`some avg10`
+    pub cpu_pressure: History,
+    pub memory_pressure: History,
+    pub io_pressure: History,
+    /// ブロックデバイスごとのI/O
+    pub disks: BTreeMap<String, DeviceHistory>,
+}
+
+/// 読み書きは毎秒のバイト数、利用率は%
+#[derive(Debug, Default)]
+pub struct DeviceHistory {
+    pub read: History,
+    pub write: History,
+    pub util: History,
+    /// 最後に値を積んだ時刻
+    seen_at: Option<Instant>,
+}
+
+impl SystemHistory {
+    /// 今回見えたデバイスの値を積む。抜いたUSBやloopデバイスで増え続けないよう、
+    /// 履歴の最長の期間ずっと見えなかったデバイスは消す
+    pub fn push_disks(&mut self, now: Instant, disk_io: &[DiskIo]) {
+        for device in disk_io {
+            let disk = self.disks.entry(device.name.clone()).or_default();
+            disk.read.push(now, device.read_bytes as f64);
+            disk.write.push(now, device.write_bytes as f64);
+            disk.util.push(now, device.util);
+            disk.seen_at = Some(now);
+        }
+        let span = HistoryWindow::OneHour.duration();
+        self.disks.retain(|_, disk| {
+            disk.seen_at
+                .is_some_and(|seen_at| now.duration_since(seen_at) < span)
+        });
+    }
+
+    pub fn pressure(&self, resource: PressureResource) -> &History {
+        match resource {
+            PressureResource::Cpu => &self.cpu_pressure,
+            PressureResource::Memory => &self.memory_pressure,
+            PressureResource::Io => &self.io_pressure,
+        }
+    }
+
+    pub fn pressure_mut(&mut self, resource: PressureResource) -> &mut History {
+        match resource {
+            PressureResource::Cpu => &mut self.cpu_pressure,
+            PressureResource::Memory => &mut self.memory_pressure,
+            PressureResource::Io => &mut self.io_pressure,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use shared::error::AppResult;
+
+    use crate::system::diskstats::DiskIo;
+
+    use super::{History, HistoryWindow, SystemHistory};
+
+    /// 1秒ごとに`values`を入れる
+    fn history(values: impl IntoIterator<Item = f64>) -> History {
+        let start = Instant::now();
+        let mut history = History::default();
+        let mut secs = 0;
+        for value in values {
+            history.push(start + Duration::from_secs(secs), value);
+            secs += 1;
+        }
+        // 最後の値を確定させる
+        history.push(start + Duration::from_secs(secs), 0.);
+        history
+    }
+
+    #[test]
+    fn test_push_averages_per_second() -> AppResult<()> {
+        let start = Instant::now();
+        let mut history = History::default();
+        history.push(start, 10.);
+        history.push(start + Duration::from_millis(200), 30.);
+        history.push(start + Duration::from_millis(400), f64::NAN);
+        assert!(history.is_empty());
+
+        history.push(start + Duration::from_millis(1000), 50.);
+        assert_eq!(Some(20.), history.latest());
+        Ok(())
+    }
+
+    #[test]
+    fn test_capacity() -> AppResult<()> {
+        let start = Instant::now();
+        let mut history = History::new(Duration::from_secs(3));
+        (0..10).for_each(|secs| history.push(start + Duration::from_secs(secs), secs as f64));
+
+        assert_eq!(3, history.len());
+        assert_eq!(Some(8.), history.latest());
+        Ok(())
+    }
+
+    #[test]
+    fn test_downsample() -> AppResult<()> {
+        let history = history((0..120).map(f64::from));
+
+        // 60秒の窓は直近60個
+        let minute = history.downsample(HistoryWindow::OneMinute, 100);
+        assert_eq!(60, minute.len());
+        assert_eq!(Some(&60.), minute.first());
+
+        // 幅に収まるよう最大値でまとめる
+        let narrow = history.downsample(HistoryWindow::OneMinute, 30);
+        assert_eq!(30, narrow.len());
+        assert_eq!(Some(&61.), narrow.first());
+        assert_eq!(Some(&119.), narrow.last());
+
+        // 5分の窓では120秒分だけがデータのある区間になる
+        let five = history.downsample(HistoryWindow::FiveMinutes, 30);
+        assert_eq!(12, five.len());
+        assert_eq!(Some(&119.), five.last());
+        Ok(())
+    }
+
+    #[test]
+    fn test_push_disks() -> AppResult<()> {
+        let start = Instant::now();
+        let device = |name: &str| DiskIo {
+            name: name.into(),
+            util: 50.,
+            ..Default::default()
+        };
+        let mut history = SystemHistory::default();
+        history.push_disks(start, &[device("sda"), device("loop0")]);
+        history.push_disks(start + Duration::from_secs(1), &[device("sda")]);
+        assert_eq!(Some(50.), history.disks["sda"].util.latest());
+
+        // 1時間見えなければ消える
+        let hour = HistoryWindow::OneHour.duration();
+        history.push_disks(start + hour - Duration::from_secs(1), &[device("sda")]);
+        assert!(history.disks.contains_key("loop0"));
+        history.push_disks(start + hour, &[device("sda")]);
+        assert_eq!(vec!["sda"], history.disks.keys().collect::<Vec<_>>());
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use sysinfo::{LoadAvg, System};
+
+use super::{stat::ProcStat, SysInfo};
+
+pub trait Load {
+    /// 1分、5分、15分の平均
+    fn get_load_average(&self) -> LoadAvg;
+    /// 実行可能なタスク数とI/O待ちで止まっているタスク数
+    fn get_run_queue(&self) -> (u64, u64);
+    /// 毎秒のコンテキストスイッチ数
+    fn get_context_switch_rate(&self) -> u64;
+    /// 毎秒の割り込み数
+    fn get_interrupt_rate(&self) -> u64;
+}
+
+impl Load for SysInfo {
+    fn get_load_average(&self) -> LoadAvg {
+        System::load_average()
+    }
+
+    fn get_run_queue(&self) -> (u64, u64) {
+        self.stat
+            .as_ref()
+            .map_or((0, 0), |stat| (stat.procs_running, stat.procs_blocked))
+    }
+
+    fn get_context_switch_rate(&self) -> u64 {
+        self.stat_rate(|stat| stat.context_switches)
+    }
+
+    fn get_interrupt_rate(&self) -> u64 {
+        self.stat_rate(|stat| stat.interrupts)
+    }
+}
+
+impl SysInfo {
+    /// /proc/statの累計値を前回との差分から毎秒の値にする。初回の更新前は0
+    fn stat_rate(&self, counter: impl Fn(&ProcStat) -> u64) -> u64 {
+        match (&self.stat, &self.prev_stat) {
+            (Some(stat), Some(prev)) => {
+                Self::per_second(counter(stat).saturating_sub(counter(prev)), self.elapsed)
+            }
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use shared::error::AppResult;
+
+    use crate::system::{load::Load, stat::ProcStat, SysInfo};
+
+    #[test]
+    fn test_get_load() -> AppResult<()> {
+        let mut sysinfo = SysInfo::new();
+        assert_eq!(0, sysinfo.get_context_switch_rate());
+        assert!(sysinfo.get_run_queue().0 >= 1);
+
+        // 暇なマシンでは割り込みもコンテキストスイッチも0になりうるので値は見ない
+        std::thread::sleep(Duration::from_millis(200));
+        sysinfo.refresh_all();
+        assert!(sysinfo.get_load_average().one >= 0.);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stat_rate() -> AppResult<()> {
+        let mut sysinfo = SysInfo::new();
+        let prev = ProcStat {
+            context_switches: 1000,
+            interrupts: 5000,
+            ..Default::default()
+        };
+        sysinfo.stat = Some(ProcStat {
+            context_switches: 1600,
+            interrupts: 5200,
+            procs_running: 3,
+            procs_blocked: 1,
+            ..prev.clone()
+        });
+        sysinfo.prev_stat = Some(prev);
+        sysinfo.elapsed = Duration::from_millis(200);
+
+        assert_eq!(3000, sysinfo.get_context_switch_rate());
+        assert_eq!(1000, sysinfo.get_interrupt_rate());
+        assert_eq!((3, 1), sysinfo.get_run_queue());
+
+        // 前回の値がなければ0
+        sysinfo.prev_stat = None;
+        assert_eq!(0, sysinfo.get_interrupt_rate());
+        Ok(())
+    }
+}
@@ -1,9 +1,99 @@
-use super::SysInfo;
+use std::{collections::HashMap, fs, path::Path};
+
+use shared::error::AppResult;
+
+use super::{oom::OomKill, SysInfo, PROC_ROOT};
+
+/// /proc/meminfoの内訳。HugePagesの枚数以外はバイト数
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemInfo {
+    pub total: u64,
+    pub free: u64,
+    pub available: u64,
+    pub buffers: u64,
+    /// tmpfsなどの`shared`を含む
+    pub cached: u64,
+    pub shared: u64,
+    pub slab_reclaimable: u64,
+    pub slab_unreclaimable: u64,
+    pub dirty: u64,
+    pub writeback: u64,
+    pub huge_pages_total: u64,
+    pub huge_pages_free: u64,
+    pub huge_page_size: u64,
+    pub committed_as: u64,
+    pub commit_limit: u64,
+    /// zswapのプールの大きさと、そこに入っているページの圧縮前の大きさ
+    pub zswap: u64,
+    pub zswapped: u64,
+}
+
+impl MemInfo {
+    pub fn read(proc_root: &Path) -> AppResult<Self> {
+        Ok(parse_meminfo(&fs::read_to_string(proc_root.join("meminfo"))?))
+    }
+
+    /// `free`コマンドと同じく、回収できるslabもキャッシュに数える
+    pub fn cache(&self) -> u64 {
+        self.cached + self.slab_reclaimable
+    }
+
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.free + self.buffers + self.cache())
+    }
+
+    /// 積み上げ表示の順。残りはfree
+    pub fn segments(&self) -> [(&'static str, u64); 3] {
+        [
+            ("used", self.used()),
+            ("buf", self.buffers),
+            ("cache", self.cache()),
+        ]
+    }
+}
+
+/// `MemTotal:  1024 kB`の形の行を読む。単位のない行は枚数なのでそのまま
+pub fn parse_meminfo(content: &str) -> MemInfo {
+    let fields = content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let mut value = value.split_whitespace();
+            let number = value.next()?.parse::<u64>().ok()?;
+            let scale = if value.next() == Some("kB") { 1024 } else { 1 };
+            Some((key.trim(), number * scale))
+        })
+        .collect::<HashMap<_, _>>();
+    let field = |key| fields.get(key).copied().unwrap_or_default();
+    MemInfo {
+        total: field("MemTotal"),
+        free: field("MemFree"),
+        available: field("MemAvailable"),
+        buffers: field("Buffers"),
+        cached: field("Cached"),
+        shared: field("Shmem"),
+        slab_reclaimable: field("SReclaimable"),
+        slab_unreclaimable: field("SUnreclaim"),
+        dirty: field("Dirty"),
+        writeback: field("Writeback"),
+        huge_pages_total: field("HugePages_Total"),
+        huge_pages_free: field("HugePages_Free"),
+        huge_page_size: field("Hugepagesize"),
+        committed_as: field("Committed_AS"),
+        commit_limit: field("CommitLimit"),
+        zswap: field("Zswap"),
+        zswapped: field("Zswapped"),
+    }
+}
 
 pub trait Memory {
     fn refresh_mem(&mut self);
     fn get_memory(&self)->f64;
     fn get_total_memory(&self)->u64;
+    /// /proc/meminfoの内訳
+    fn get_meminfo(&self)->AppResult<MemInfo>;
+    /// 直前の更新で起きたOOM kill
+    fn get_oom_kills(&self)->Vec<OomKill>;
 }
 
 impl Memory for SysInfo {
@@ -21,13 +111,40 @@ impl Memory for SysInfo {
         self.system.total_memory() / Self::GIB
     }
 
+    fn get_meminfo(&self) -> AppResult<MemInfo> {
+        MemInfo::read(Path::new(PROC_ROOT))
+    }
+
+    fn get_oom_kills(&self) -> Vec<OomKill> {
+        self.oom_kills.clone()
+    }
+
 }
 
 #[cfg(test)]
 mod test{
     use shared::error::AppResult;
 
-    use crate::system::{memory::Memory, SysInfo};
+    use crate::system::{memory::{parse_meminfo, Memory}, SysInfo};
+
+    const MEMINFO: &str = "\
+MemTotal:        8000000 kB
+MemFree:         1000000 kB
+MemAvailable:    5000000 kB
+Buffers:          200000 kB
+Cached:          3000000 kB
+SwapCached:            0 kB
+Shmem:            100000 kB
+SReclaimable:     300000 kB
+SUnreclaim:        50000 kB
+Dirty:              1200 kB
+Writeback:             0 kB
+CommitLimit:     4000000 kB
+Committed_AS:    6000000 kB
+HugePages_Total:      16
+HugePages_Free:        4
+Hugepagesize:       2048 kB
+";
 
     #[test]
     fn test_get_total_memory()->AppResult<()>{
@@ -36,6 +153,21 @@ mod test{
 
         Ok(())
     }
+    #[test]
+    fn test_parse_meminfo()->AppResult<()>{
+        let info = parse_meminfo(MEMINFO);
+        assert_eq!(8000000 * 1024, info.total);
+        assert_eq!(16, info.huge_pages_total);
+        assert_eq!(2048 * 1024, info.huge_page_size);
+        assert_eq!(3300000 * 1024, info.cache());
+        assert_eq!(3500000 * 1024, info.used());
+        assert_eq!(6000000 * 1024, info.committed_as);
+
+        let info = SysInfo::new().get_meminfo()?;
+        assert!(info.total > 0 && info.available <= info.total);
+        Ok(())
+    }
+
     #[test]
     fn test_get_memory()->AppResult<()>{
         let si = SysInfo::new();
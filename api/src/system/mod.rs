@@ -1,28 +1,81 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashSet,
+    path::Path,
+    time::{Duration, Instant},
+};
 
+use disk::Disk;
+use diskstats::{read_diskstats, DiskStat};
 use log::error;
+use oom::{CgroupOomCounts, OomKill};
+use process::cgroup::OwnerCache;
 use shared::error::{AppError, AppResult};
-use sysinfo::{Disks, Networks, Pid, Process, System};
+use stat::ProcStat;
+#[cfg(not(target_os = "linux"))]
+use sysinfo::Components;
+use sysinfo::{Disks, Networks, System, Uid, Users};
+use vmstat::VmStat;
 
 pub mod cpu;
 pub mod disk;
+pub mod diskstats;
+pub mod frequency;
+pub mod history;
+pub mod load;
 pub mod memory;
 pub mod network;
+pub mod oom;
+pub mod pressure;
+pub mod socket;
+pub mod stat;
 pub mod swap;
+pub mod vmstat;
 pub mod process;
+pub mod sensor;
 pub mod prelude;
+#[cfg(test)]
+mod fixture;
 
 const UNKONW: &str = "unkonw";
+const PROC_ROOT: &str = "/proc";
+const SYS_ROOT: &str = "/sys";
 
 pub struct SysInfo {
     system: System,
     disks: Disks,
+    /// statvfsはマウントの数だけ呼ぶので間隔を空けて更新する
+    disks_refreshed_at: Instant,
     networks: Networks,
+    users: Users,
+    /// ユーザー一覧を読み直しても名前の分からなかったUID
+    unknown_uids: HashSet<Uid>,
+    /// 温度センサー。Sensorsタブを開いている間だけ更新する。Linuxではhwmonを直接読む
+    #[cfg(not(target_os = "linux"))]
+    components: Components,
+    refreshed_at: Instant,
+    /// 直前の更新からの経過時間。差分から毎秒の値を出すのに使う
+    elapsed: Duration,
+    /// /proc/statの今回と前回の値。CPU時間の内訳は差分から出す
+    stat: Option<ProcStat>,
+    prev_stat: Option<ProcStat>,
+    /// /proc/vmstatの今回と前回の値
+    vmstat: Option<VmStat>,
+    prev_vmstat: Option<VmStat>,
+    /// 今回の更新で見つけたOOM killと、それを見つけるためのcgroupごとの回数
+    oom_kills: Vec<OomKill>,
+    cgroup_oom_counts: CgroupOomCounts,
+    /// 最後に読めたvmstatの`oom_kill`。読めない回があっても基準はこれを使い続ける
+    oom_kill_total: Option<u64>,
+    /// /proc/diskstatsの今回と前回の値
+    diskstats: Vec<DiskStat>,
+    prev_diskstats: Vec<DiskStat>,
+    /// プロセスごとのcgroupの持ち主
+    cgroup_owners: OwnerCache,
 }
 
 impl SysInfo {
-    const BYTE: u64 = 1024;
     const GIB: u64 = 1024 * 1024 * 1024;
+    const DISKS_INTERVAL: Duration = Duration::from_secs(2);
 
     fn new() -> Self {
         let mut sys = System::new_all();
@@ -31,16 +84,63 @@ impl SysInfo {
         let disks = Disks::new_with_refreshed_list();
 
         let networks = Networks::new_with_refreshed_list();
-        Self {
+        let users = Users::new_with_refreshed_list();
+        let vmstat = VmStat::read(Path::new(PROC_ROOT)).ok();
+        let mut info = Self {
             system: sys,
-            disks:disks,
-            networks:networks,
-        }
+            disks,
+            disks_refreshed_at: Instant::now(),
+            networks,
+            users,
+            unknown_uids: HashSet::new(),
+            #[cfg(not(target_os = "linux"))]
+            components: Components::new_with_refreshed_list(),
+            refreshed_at: Instant::now(),
+            elapsed: Duration::ZERO,
+            stat: ProcStat::read(Path::new(PROC_ROOT)).ok(),
+            prev_stat: None,
+            vmstat,
+            prev_vmstat: None,
+            oom_kills: vec![],
+            cgroup_oom_counts: Self::initial_oom_counts(),
+            oom_kill_total: vmstat.map(|vmstat| vmstat.oom_kill),
+            diskstats: read_diskstats(Path::new(PROC_ROOT)).unwrap_or_default(),
+            prev_diskstats: vec![],
+            cgroup_owners: OwnerCache::default(),
+        };
+        info.refresh_cgroup_owners();
+        info
     }
 
     #[inline]
     pub fn refresh_all(&mut self) {
         self.system.refresh_all();
+        self.refresh_cgroup_owners();
+        self.refresh_users();
+        self.networks.refresh();
+        if self.disks_refreshed_at.elapsed() >= Self::DISKS_INTERVAL {
+            self.refresh_disks();
+        }
+        self.prev_stat = self.stat.take();
+        self.stat = ProcStat::read(Path::new(PROC_ROOT)).ok();
+        self.prev_vmstat = self.vmstat.take();
+        self.vmstat = VmStat::read(Path::new(PROC_ROOT)).ok();
+        self.detect_oom_kills();
+        self.prev_diskstats = std::mem::take(&mut self.diskstats);
+        self.diskstats = read_diskstats(Path::new(PROC_ROOT)).unwrap_or_default();
+
+        let now = Instant::now();
+        self.elapsed = now - self.refreshed_at;
+        self.refreshed_at = now;
+    }
+
+    /// 前回の更新から増えた量を毎秒の値にする
+    fn per_second(delta: u64, elapsed: Duration) -> u64 {
+        if elapsed.is_zero() {
+            0
+        } else {
+            (delta as f64 / elapsed.as_secs_f64()) as u64
+        }
     }
 }
 
@@ -100,14 +200,6 @@ impl Default for SysData {
     }
 }
 
-fn get_os() -> String {
-    if let Some(os) = System::name() {
-        os
-    } else {
-        UNKONW.into()
-    }
-}
-
 fn get_host() -> String {
     if let Some(host) = System::host_name() {
         host
@@ -116,14 +208,6 @@ fn get_host() -> String {
     }
 }
 
-fn get_os_version() -> String {
-    if let Some(ver) = System::os_version() {
-        ver
-    } else {
-        UNKONW.into()
-    }
-}
-
 fn get_cpu_arch() -> String {
     if let Some(arch) = System::cpu_arch() {
         arch
@@ -157,9 +241,7 @@ fn get_kernel_ver() -> String {
 }
 
 pub fn get_networks_data() -> Networks {
-    let net = Networks::new_with_refreshed_list();
-    for (str, n) in net.list() {}
-    net
+    Networks::new_with_refreshed_list()
 }
 
 pub fn supported() -> AppResult<()> {
@@ -170,3 +252,19 @@ pub fn supported() -> AppResult<()> {
         Err(AppError::NoSupported)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use shared::error::AppResult;
+
+    use super::SysInfo;
+
+    #[test]
+    fn test_per_second() -> AppResult<()> {
+        assert_eq!(2048, SysInfo::per_second(1024, Duration::from_millis(500)));
+        assert_eq!(0, SysInfo::per_second(1024, Duration::ZERO));
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 
-use sysinfo::{IpNetwork, MacAddr};
+use sysinfo::MacAddr;
 
 use super::SysInfo;
 
@@ -40,6 +40,8 @@ pub trait Networks {
     
     fn refresh_networks(&mut self);
     fn get_networks_info(&self)->Vec<NetworkData>;
+    /// 全インターフェースの毎秒の受信・送信バイト数
+    fn get_network_rates(&self)->(u64,u64);
 }
 
 impl Networks for SysInfo {
@@ -60,6 +62,17 @@ impl Networks for SysInfo {
             NetworkData { name: name.clone().to_owned(), ip_addr: ip_network_opt,mac_addr: net.mac_address()}
         }).collect()
     }
+
+    fn get_network_rates(&self) -> (u64, u64) {
+        let (received, transmitted) = self
+            .networks
+            .iter()
+            .fold((0, 0), |(rx, tx), (_, net)| (rx + net.received(), tx + net.transmitted()));
+        (
+            Self::per_second(received, self.elapsed),
+            Self::per_second(transmitted, self.elapsed),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,208 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use super::{pressure::CGROUP_ROOT, SysInfo};
+
+/// 1回のOOM killer。cgroup v2の`memory.events`から分かればそのcgroupのパス
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OomKill {
+    pub cgroup: Option<String>,
+}
+
+/// cgroupのパスごとの`memory.events`の`oom_kill`。ルートは`memory.events`を持たない
+pub type CgroupOomCounts = HashMap<String, u64>;
+
+/// `<cgroup_root>`以下を辿って`memory.events`を読む。cgroup v1だけの環境では空
+pub fn read_cgroup_oom_kills(cgroup_root: &Path) -> CgroupOomCounts {
+    let mut counts = HashMap::new();
+    let mut dirs = vec![cgroup_root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        dirs.extend(
+            entries
+                .flatten()
+                .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+                .map(|entry| entry.path()),
+        );
+        let count = fs::read_to_string(dir.join("memory.events"))
+            .ok()
+            .and_then(|content| parse_oom_kill(&content));
+        let path = dir
+            .strip_prefix(cgroup_root)
+            .ok()
+            .map(|path| format!("/{}", path.display()));
+        if let (Some(count), Some(path)) = (count, path) {
+            counts.insert(path, count);
+        }
+    }
+    counts
+}
+
+pub fn parse_oom_kill(content: &str) -> Option<u64> {
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("oom_kill "))
+        .and_then(|count| count.trim().parse().ok())
+}
+
+/// `oom_kill`が増えたcgroupから1回ずつ`OomKill`を作る。
+/// 親のcgroupにも子の分が足されるので、増えた子孫を持たない一番深いものだけを数える。
+/// cgroupで説明のつかない残りは`cgroup`なしにする
+pub fn oom_kills(total: u64, prev: &CgroupOomCounts, next: &CgroupOomCounts) -> Vec<OomKill> {
+    let increased = next
+        .iter()
+        .filter_map(|(path, count)| {
+            let delta = count.saturating_sub(prev.get(path).copied().unwrap_or_default());
+            (delta > 0).then_some((path.as_str(), delta))
+        })
+        .collect::<Vec<_>>();
+    let mut leaves = increased
+        .iter()
+        .filter(|(path, _)| {
+            !increased
+                .iter()
+                .any(|(other, _)| other.strip_prefix(path).is_some_and(|rest| rest.starts_with('/')))
+        })
+        .copied()
+        .collect::<Vec<_>>();
+    leaves.sort();
+
+    let mut kills = leaves
+        .iter()
+        .flat_map(|(path, delta)| {
+            (0..*delta).map(|_| OomKill {
+                cgroup: Some(path.to_string()),
+            })
+        })
+        .take(total as usize)
+        .collect::<Vec<_>>();
+    let rest = total as usize - kills.len();
+    kills.extend((0..rest).map(|_| OomKill { cgroup: None }));
+    kills
+}
+
+impl SysInfo {
+    /// /proc/vmstatの`oom_kill`が増えた時だけcgroupを読み直す。
+    /// vmstatが読めなかった回の分は、次に読めた時に最後に読めた値との差で出す
+    pub(super) fn detect_oom_kills(&mut self) {
+        self.oom_kills.clear();
+        let Some(current) = self.vmstat.map(|vmstat| vmstat.oom_kill) else {
+            return;
+        };
+        match self.oom_kill_total.replace(current) {
+            Some(prev) if current > prev => {
+                let counts = read_cgroup_oom_kills(Path::new(CGROUP_ROOT));
+                self.oom_kills = oom_kills(current - prev, &self.cgroup_oom_counts, &counts);
+                self.cgroup_oom_counts = counts;
+            }
+            Some(_) => {}
+            // 起動時に読めなかったら、初めて読めた時点をcgroupの回数の基準にもする
+            None => self.cgroup_oom_counts = read_cgroup_oom_kills(Path::new(CGROUP_ROOT)),
+        }
+    }
+
+    /// 起動時点の値。これより前のOOMは出さない
+    pub(super) fn initial_oom_counts() -> CgroupOomCounts {
+        read_cgroup_oom_kills(Path::new(CGROUP_ROOT))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, fs};
+
+    use shared::error::AppResult;
+
+    use crate::system::fixture::TempDir;
+
+    use crate::system::{vmstat::VmStat, SysInfo};
+
+    use super::{oom_kills, parse_oom_kill, read_cgroup_oom_kills, OomKill};
+
+    const MEMORY_EVENTS: &str = "low 0\nhigh 12\nmax 40\noom 3\noom_kill 2\noom_group_kill 0\n";
+
+    #[test]
+    fn test_read_cgroup_oom_kills() -> AppResult<()> {
+        let root = TempDir::new("oom")?;
+        let service = root.join("system.slice").join("db.service");
+        fs::create_dir_all(&service)?;
+        fs::create_dir_all(root.join("init.scope"))?;
+        fs::write(root.join("system.slice").join("memory.events"), MEMORY_EVENTS)?;
+        fs::write(service.join("memory.events"), MEMORY_EVENTS)?;
+
+        let counts = read_cgroup_oom_kills(&root);
+
+        assert_eq!(
+            HashMap::from([
+                ("/system.slice".to_owned(), 2),
+                ("/system.slice/db.service".to_owned(), 2),
+            ]),
+            counts
+        );
+        assert_eq!(None, parse_oom_kill("low 0\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_oom_kills() -> AppResult<()> {
+        let counts = |entries: &[(&str, u64)]| {
+            entries
+                .iter()
+                .map(|(path, count)| (path.to_string(), *count))
+                .collect::<HashMap<_, _>>()
+        };
+        let prev = counts(&[("/system.slice", 1), ("/system.slice/db.service", 1), ("/user.slice", 0)]);
+        let next = counts(&[
+            ("/system.slice", 3),
+            ("/system.slice/db.service", 2),
+            ("/system.slice/web.service", 1),
+            ("/user.slice", 0),
+        ]);
+        let cgroup = |path: &str| OomKill {
+            cgroup: Some(path.into()),
+        };
+
+        assert_eq!(
+            vec![
+                cgroup("/system.slice/db.service"),
+                cgroup("/system.slice/web.service"),
+                OomKill { cgroup: None },
+            ],
+            oom_kills(3, &prev, &next)
+        );
+        // cgroupが読めなくても回数だけは出す
+        assert_eq!(vec![OomKill { cgroup: None }], oom_kills(1, &prev, &prev));
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_oom_kills() -> AppResult<()> {
+        let mut sysinfo = SysInfo::new();
+        let vmstat = |oom_kill| {
+            Some(VmStat {
+                oom_kill,
+                ..Default::default()
+            })
+        };
+        sysinfo.oom_kill_total = Some(2);
+
+        // 読めなかった回は基準を残し、その間のOOMは次に読めた時に出す
+        sysinfo.vmstat = None;
+        sysinfo.detect_oom_kills();
+        assert!(sysinfo.oom_kills.is_empty());
+        sysinfo.vmstat = vmstat(4);
+        sysinfo.detect_oom_kills();
+        assert_eq!(2, sysinfo.oom_kills.len());
+        sysinfo.detect_oom_kills();
+        assert!(sysinfo.oom_kills.is_empty());
+
+        // 基準がなければその時点から数える
+        sysinfo.oom_kill_total = None;
+        sysinfo.vmstat = vmstat(9);
+        sysinfo.detect_oom_kills();
+        assert!(sysinfo.oom_kills.is_empty());
+        assert_eq!(Some(9), sysinfo.oom_kill_total);
+        Ok(())
+    }
+}
@@ -1 +1 @@
-pub use super::{cpu::Cpu,memory::Memory,disk::Disk,network::Networks,swap::Swap,process::SysProcess,};
\ No newline at end of file
+pub use super::{cpu::Cpu,load::Load,memory::Memory,disk::Disk,network::Networks,swap::Swap,process::{signal::SysSignal, SysProcess},socket::SysSocket,pressure::SysPressure,sensor::SysSensor,frequency::SysFrequency,};
\ No newline at end of file
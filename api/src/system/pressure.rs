@@ -0,0 +1,188 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use shared::error::{AppError, AppResult};
+use strum::{Display, EnumIter};
+
+use super::{SysInfo, PROC_ROOT};
+
+/// cgroup v2の階層。hybrid構成では`unified`の下にある
+pub const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+const PSI: &str = "Pressure stall information";
+
+#[derive(Debug, Display, EnumIter, Clone, Copy, PartialEq, Eq)]
+pub enum PressureResource {
+    #[strum(to_string = "cpu")]
+    Cpu,
+    #[strum(to_string = "memory")]
+    Memory,
+    #[strum(to_string = "io")]
+    Io,
+}
+
+/// 直近10秒、60秒、300秒で待たされていた時間の割合(%)と累計のマイクロ秒
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PressureAvg {
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+    pub total: u64,
+}
+
+/// `some`は一部のタスクが、`full`は全タスクが待たされていた割合。
+/// 古いカーネルのcpuには`full`の行がない
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Pressure {
+    pub some: PressureAvg,
+    pub full: Option<PressureAvg>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PressureSnapshot {
+    pub cpu: Pressure,
+    pub memory: Pressure,
+    pub io: Pressure,
+}
+
+impl PressureSnapshot {
+    pub fn get(&self, resource: PressureResource) -> &Pressure {
+        match resource {
+            PressureResource::Cpu => &self.cpu,
+            PressureResource::Memory => &self.memory,
+            PressureResource::Io => &self.io,
+        }
+    }
+
+    /// `/proc/pressure/{cpu,memory,io}`を読む
+    pub fn read_system(proc_root: &Path) -> AppResult<Self> {
+        let dir = proc_root.join("pressure");
+        Self::read_with(|resource| dir.join(resource.to_string()))
+    }
+
+    /// cgroup v2の`{cpu,memory,io}.pressure`を読む。`path`は`/proc/<pid>/cgroup`の`0::`の値
+    pub fn read_cgroup(cgroup_root: &Path, path: &str) -> AppResult<Self> {
+        let dir = cgroup_root.join(path.trim_start_matches('/'));
+        Self::read_with(|resource| dir.join(format!("{resource}.pressure")))
+    }
+
+    fn read_with(file: impl Fn(PressureResource) -> PathBuf) -> AppResult<Self> {
+        let read = |resource| -> AppResult<Pressure> {
+            let content = fs::read_to_string(file(resource)).map_err(|err| match err.kind() {
+                // PSIなしでビルドされたカーネルではファイルがなく、
+                // `psi=0`で起動した場合は読むとEOPNOTSUPPになる
+                io::ErrorKind::NotFound | io::ErrorKind::Unsupported => {
+                    AppError::NotAvailable(PSI)
+                }
+                _ if err.raw_os_error() == Some(libc::EOPNOTSUPP) => AppError::NotAvailable(PSI),
+                _ => err.into(),
+            })?;
+            parse_pressure(&content).ok_or(AppError::NotAvailable(PSI))
+        };
+        Ok(Self {
+            cpu: read(PressureResource::Cpu)?,
+            memory: read(PressureResource::Memory)?,
+            io: read(PressureResource::Io)?,
+        })
+    }
+}
+
+/// `some avg10=0.00 avg60=0.00 avg300=0.00 total=0`の形の行を読む
+pub fn parse_pressure(content: &str) -> Option<Pressure> {
+    let mut some = None;
+    let mut full = None;
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let kind = fields.next()?;
+        let mut avg = PressureAvg::default();
+        for field in fields {
+            let (key, value) = field.split_once('=')?;
+            match key {
+                "avg10" => avg.avg10 = value.parse().ok()?,
+                "avg60" => avg.avg60 = value.parse().ok()?,
+                "avg300" => avg.avg300 = value.parse().ok()?,
+                "total" => avg.total = value.parse().ok()?,
+                _ => {}
+            }
+        }
+        match kind {
+            "some" => some = Some(avg),
+            "full" => full = Some(avg),
+            _ => {}
+        }
+    }
+    Some(Pressure { some: some?, full })
+}
+
+pub trait SysPressure {
+    /// PSIに対応していないカーネルでは`AppError::NotAvailable`
+    fn get_pressure(&self) -> AppResult<PressureSnapshot>;
+}
+
+impl SysPressure for SysInfo {
+    fn get_pressure(&self) -> AppResult<PressureSnapshot> {
+        PressureSnapshot::read_system(Path::new(PROC_ROOT))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use shared::error::{AppError, AppResult};
+
+    use crate::system::fixture::TempDir;
+
+    use super::{parse_pressure, PressureAvg, PressureResource, PressureSnapshot};
+
+    const MEMORY: &str = "\
+some avg10=1.50 avg60=0.75 avg300=0.20 total=123456
+full avg10=0.50 avg60=0.25 avg300=0.05 total=65432
+";
+
+    #[test]
+    fn test_parse_pressure() -> AppResult<()> {
+        let pressure = parse_pressure(MEMORY);
+        assert_eq!(
+            Some(PressureAvg {
+                avg10: 1.5,
+                avg60: 0.75,
+                avg300: 0.2,
+                total: 123456,
+            }),
+            pressure.map(|p| p.some)
+        );
+        assert_eq!(Some(0.5), pressure.and_then(|p| p.full).map(|full| full.avg10));
+
+        // 古いカーネルのcpuは`some`だけ
+        let cpu = parse_pressure("some avg10=3.00 avg60=2.00 avg300=1.00 total=1\n");
+        assert_eq!(Some(None), cpu.map(|p| p.full));
+        assert_eq!(None, parse_pressure("full avg10=0.00 avg60=0.00 avg300=0.00 total=0\n"));
+        assert_eq!(None, parse_pressure("some avg10=x\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_pressure() -> AppResult<()> {
+        let root = TempDir::new("pressure")?;
+        let cgroup = root.join("system.slice").join("sshd.service");
+        std::fs::create_dir_all(root.join("pressure"))?;
+        std::fs::create_dir_all(&cgroup)?;
+        for resource in ["cpu", "memory", "io"] {
+            std::fs::write(root.join("pressure").join(resource), MEMORY)?;
+            std::fs::write(cgroup.join(format!("{resource}.pressure")), MEMORY)?;
+        }
+
+        let system = PressureSnapshot::read_system(&root);
+        let unit = PressureSnapshot::read_cgroup(&root, "/system.slice/sshd.service");
+
+        assert_eq!(1.5, system?.get(PressureResource::Io).some.avg10);
+        assert_eq!(123456, unit?.cpu.some.total);
+        assert!(matches!(
+            PressureSnapshot::read_system(Path::new("/nonexistent")),
+            Err(AppError::NotAvailable(_))
+        ));
+        Ok(())
+    }
+}
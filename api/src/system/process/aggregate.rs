@@ -0,0 +1,180 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use strum::Display;
+
+use super::{ProcessColumn, ProcessFilter, ProcessRow, ProcessSort, SortOrder};
+
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    #[strum(to_string = "User")]
+    User,
+    #[strum(to_string = "Command")]
+    Command,
+    #[strum(to_string = "Cgroup")]
+    Cgroup,
+}
+
+impl GroupBy {
+    fn key(self, row: &ProcessRow) -> String {
+        match self {
+            Self::User => row.user.clone(),
+            Self::Command => row.name.clone(),
+            Self::Cgroup => row.cgroup_label(),
+        }
+    }
+}
+
+/// ユーザーやコマンドごとに合計した使用量
+#[derive(Debug, Clone)]
+pub struct ProcessGroup {
+    pub key: String,
+    pub count: usize,
+    /// 所属プロセスの使用量の合計。PIDなどの項目は意味を持たない
+    pub total: ProcessRow,
+}
+
+impl ProcessGroup {
+    fn compare(&self, other: &ProcessGroup, sort: &ProcessSort) -> Ordering {
+        let ordering = match sort.column {
+            ProcessColumn::Cpu
+            | ProcessColumn::Rss
+            | ProcessColumn::Virt
+            | ProcessColumn::ReadRate
+            | ProcessColumn::WriteRate
+            | ProcessColumn::DiskRead
+            | ProcessColumn::DiskWrite => sort.column.compare(&self.total, &other.total),
+            ProcessColumn::Name
+            | ProcessColumn::User
+            | ProcessColumn::Command
+            | ProcessColumn::Cgroup => self.key.cmp(&other.key),
+            // 使用量以外の列ではプロセス数で並べる
+            _ => self.count.cmp(&other.count),
+        };
+        let ordering = match sort.order {
+            SortOrder::Ascending => ordering,
+            SortOrder::Descending => ordering.reverse(),
+        };
+        ordering.then_with(|| self.key.cmp(&other.key))
+    }
+}
+
+pub fn aggregate(
+    rows: &[ProcessRow],
+    by: GroupBy,
+    filter: &ProcessFilter,
+    sort: &ProcessSort,
+) -> Vec<ProcessGroup> {
+    let mut groups: HashMap<String, ProcessGroup> = HashMap::new();
+    for row in rows.iter().filter(|row| filter.matches(row)) {
+        let key = by.key(row);
+        let group = groups.entry(key.clone()).or_insert_with(|| ProcessGroup {
+            key,
+            count: 0,
+            total: ProcessRow::default(),
+        });
+        group.count += 1;
+        group.total.add_usage(row);
+    }
+
+    let mut groups = groups.into_values().collect::<Vec<_>>();
+    groups.sort_by(|a, b| a.compare(b, sort));
+    groups
+}
+
+#[cfg(test)]
+mod test {
+    use shared::error::AppResult;
+
+    use crate::system::process::{
+        cgroup::CgroupOwner, test::row, ProcessColumn, ProcessFilter, ProcessRow, ProcessSort,
+        SortOrder,
+    };
+
+    use super::{aggregate, GroupBy};
+
+    fn rows() -> Vec<ProcessRow> {
+        let mut rows = vec![
+            row(1, "make", 10., 100),
+            row(2, "cc1", 40., 300),
+            row(3, "cc1", 30., 200),
+            row(4, "bash", 1., 50),
+        ];
+        rows[0].user = "alice".into();
+        rows[1].user = "alice".into();
+        rows[2].user = "bob".into();
+        rows[3].user = "bob".into();
+        rows[1].read_rate = 1000;
+        rows[2].read_rate = 500;
+        rows
+    }
+
+    #[test]
+    fn test_aggregate_by_user() -> AppResult<()> {
+        let sort = ProcessSort {
+            column: ProcessColumn::Cpu,
+            order: SortOrder::Descending,
+        };
+        let groups = aggregate(&rows(), GroupBy::User, &ProcessFilter::default(), &sort);
+
+        assert_eq!(2, groups.len());
+        assert_eq!("alice", groups[0].key);
+        assert_eq!(2, groups[0].count);
+        assert_eq!(50., groups[0].total.cpu_usage);
+        assert_eq!(400, groups[0].total.rss);
+        assert_eq!("bob", groups[1].key);
+        assert_eq!(31., groups[1].total.cpu_usage);
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregate_by_command() -> AppResult<()> {
+        let sort = ProcessSort {
+            column: ProcessColumn::ReadRate,
+            order: SortOrder::Descending,
+        };
+        let groups = aggregate(&rows(), GroupBy::Command, &ProcessFilter::default(), &sort);
+
+        assert_eq!(3, groups.len());
+        assert_eq!("cc1", groups[0].key);
+        assert_eq!(2, groups[0].count);
+        assert_eq!(1500, groups[0].total.read_rate);
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregate_by_cgroup() -> AppResult<()> {
+        let mut rows = rows();
+        rows[1].cgroup = Some(CgroupOwner::Unit("build.scope".into()));
+        rows[2].cgroup = Some(CgroupOwner::Unit("build.scope".into()));
+        let groups = aggregate(
+            &rows,
+            GroupBy::Cgroup,
+            &ProcessFilter::default(),
+            &ProcessSort::default(),
+        );
+
+        let keys = groups
+            .iter()
+            .map(|group| (group.key.as_str(), group.count))
+            .collect::<Vec<_>>();
+        assert_eq!(vec![("-", 2), ("build.scope", 2)], keys);
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregate_filtered_by_count() -> AppResult<()> {
+        let mut filter = ProcessFilter::default();
+        "bo".chars().for_each(|c| filter.push(c));
+        let sort = ProcessSort {
+            column: ProcessColumn::Pid,
+            order: SortOrder::Descending,
+        };
+        // bobのプロセスだけが残る
+        let groups = aggregate(&rows(), GroupBy::User, &filter, &sort);
+
+        assert_eq!(1, groups.len());
+        assert_eq!("bob", groups[0].key);
+        assert_eq!(2, groups[0].count);
+        Ok(())
+    }
+}
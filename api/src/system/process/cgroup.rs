@@ -0,0 +1,421 @@
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use strum::Display;
+use sysinfo::Pid;
+
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum Runtime {
+    #[strum(to_string = "docker")]
+    Docker,
+    #[strum(to_string = "podman")]
+    Podman,
+    #[strum(to_string = "containerd")]
+    Containerd,
+    #[strum(to_string = "crio")]
+    CriO,
+}
+
+/// cgroupのパスから分かるプロセスの持ち主
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CgroupOwner {
+    /// systemdのserviceやscope
+    Unit(String),
+    Container { runtime: Runtime, id: String },
+    Pod {
+        uid: String,
+        container: Option<String>,
+    },
+}
+
+impl CgroupOwner {
+    /// コンテナIDやPodのUIDはこの長さに縮めて表示する
+    const SHORT_ID: usize = 12;
+}
+
+impl fmt::Display for CgroupOwner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let short = |id: &str| id.chars().take(Self::SHORT_ID).collect::<String>();
+        match self {
+            Self::Unit(unit) => write!(f, "{unit}"),
+            Self::Container { runtime, id } => write!(f, "{runtime}:{}", short(id)),
+            Self::Pod { uid, .. } => write!(f, "pod:{}", short(uid)),
+        }
+    }
+}
+
+/// `/proc/<pid>/cgroup`から読む。読めない場合やどこにも属さない場合は`None`
+pub fn read_owner(proc_root: &Path, pid: Pid) -> Option<CgroupOwner> {
+    let content = fs::read_to_string(proc_root.join(pid.to_string()).join("cgroup")).ok()?;
+    parse_owner(cgroup_path(&content)?)
+}
+
+/// cgroup v2の`0::`の行のパス。v1だけの環境では`None`
+pub fn unified_path(content: &str) -> Option<&str> {
+    content.lines().find_map(|line| line.strip_prefix("0::"))
+}
+
+/// cgroup v2の`0::`の行を優先し、v1だけならsystemdの階層を使う
+pub fn cgroup_path(content: &str) -> Option<&str> {
+    let entries = content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, ':');
+            Some((fields.next()?, fields.next()?, fields.next()?))
+        })
+        .collect::<Vec<_>>();
+
+    entries
+        .iter()
+        .find(|(id, controllers, _)| *id == "0" && controllers.is_empty())
+        .or_else(|| {
+            entries
+                .iter()
+                .find(|(_, controllers, _)| *controllers == "name=systemd")
+        })
+        .or_else(|| entries.first())
+        .map(|(_, _, path)| *path)
+}
+
+/// systemdドライバ(`docker-<id>.scope`)とcgroupfsドライバ(`/docker/<id>`)の両方の形を解釈する
+pub fn parse_owner(path: &str) -> Option<CgroupOwner> {
+    let parts = path
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>();
+
+    if let Some(uid) = parts.iter().find_map(|part| pod_uid(part)) {
+        let container = parts.last().and_then(|part| {
+            container_id(part)
+                .map(|(_, id)| id)
+                .or_else(|| is_container_id(part).then_some(*part))
+        });
+        return Some(CgroupOwner::Pod {
+            uid,
+            container: container.map(str::to_owned),
+        });
+    }
+
+    for (idx, part) in parts.iter().enumerate().rev() {
+        let found = container_id(part).or_else(|| {
+            // cgroupfsドライバではIDだけのディレクトリになるので親で判断する
+            let runtime = match idx.checked_sub(1).map(|parent| parts[parent]) {
+                Some("docker") => Runtime::Docker,
+                Some("libpod_parent") => Runtime::Podman,
+                _ => return None,
+            };
+            is_container_id(part).then_some((runtime, *part))
+        });
+        if let Some((runtime, id)) = found {
+            return Some(CgroupOwner::Container {
+                runtime,
+                id: id.to_owned(),
+            });
+        }
+    }
+
+    parts
+        .iter()
+        .rev()
+        .find(|part| part.ends_with(".service") || part.ends_with(".scope"))
+        .map(|unit| CgroupOwner::Unit(unit.to_string()))
+}
+
+fn container_id(part: &str) -> Option<(Runtime, &str)> {
+    let name = part.strip_suffix(".scope").unwrap_or(part);
+    let (runtime, id) = [
+        ("docker-", Runtime::Docker),
+        ("libpod-conmon-", Runtime::Podman),
+        ("libpod-", Runtime::Podman),
+        ("cri-containerd-", Runtime::Containerd),
+        ("crio-conmon-", Runtime::CriO),
+        ("crio-", Runtime::CriO),
+    ]
+    .into_iter()
+    .find_map(|(prefix, runtime)| name.strip_prefix(prefix).map(|id| (runtime, id)))?;
+    is_container_id(id).then_some((runtime, id))
+}
+
+fn is_container_id(id: &str) -> bool {
+    id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// `kubepods-burstable-pod<uid>.slice`(uidの`-`は`_`になる)と`pod<uid>`の両方を受け付ける
+fn pod_uid(part: &str) -> Option<String> {
+    let uid = match part.strip_suffix(".slice") {
+        Some(name) => name.rsplit('-').next()?.strip_prefix("pod")?.replace('_', "-"),
+        None => part.strip_prefix("pod")?.to_owned(),
+    };
+    let is_uid = uid.len() >= 32 && uid.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+    is_uid.then_some(uid)
+}
+
+/// プロセスごとのcgroupを(PID, 起動時刻)ごとに覚えておく。
+/// 起動後に`systemd-run --scope`やコンテナのinitで移ることがあるので、`ttl`ごとに読み直す
+#[derive(Debug)]
+pub struct OwnerCache {
+    entries: HashMap<Pid, OwnerEntry>,
+    ttl: Duration,
+}
+
+#[derive(Debug)]
+struct OwnerEntry {
+    start_time: u64,
+    read_at: Instant,
+    owner: Option<CgroupOwner>,
+}
+
+impl Default for OwnerCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TTL)
+    }
+}
+
+impl OwnerCache {
+    const DEFAULT_TTL: Duration = Duration::from_secs(5);
+
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: HashMap::new(),
+            ttl,
+        }
+    }
+
+    /// 新しいプロセスと古くなったものだけ読み、いなくなったプロセスは忘れる。
+    /// 読めなかったものも`ttl`が過ぎれば読み直す
+    pub fn refresh(
+        &mut self,
+        processes: impl IntoIterator<Item = (Pid, u64)>,
+        now: Instant,
+        mut read: impl FnMut(Pid) -> Option<CgroupOwner>,
+    ) {
+        let mut entries = HashMap::with_capacity(self.entries.len());
+        for (pid, start_time) in processes {
+            let entry = match self.entries.remove(&pid) {
+                Some(entry)
+                    if entry.start_time == start_time
+                        && now.duration_since(entry.read_at) < self.ttl =>
+                {
+                    entry
+                }
+                _ => OwnerEntry {
+                    start_time,
+                    read_at: now,
+                    owner: read(pid),
+                },
+            };
+            entries.insert(pid, entry);
+        }
+        self.entries = entries;
+    }
+
+    pub fn get(&self, pid: Pid, start_time: u64) -> Option<CgroupOwner> {
+        self.entries
+            .get(&pid)
+            .filter(|entry| entry.start_time == start_time)
+            .and_then(|entry| entry.owner.clone())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        cell::{Cell, RefCell},
+        collections::HashMap,
+        path::Path,
+        time::{Duration, Instant},
+    };
+
+    use shared::error::AppResult;
+
+    use sysinfo::Pid;
+
+    use crate::system::fixture::TempDir;
+
+    use super::{
+        cgroup_path, parse_owner, read_owner, unified_path, CgroupOwner, OwnerCache, Runtime,
+    };
+
+    const ID: &str = "3f2a9c1b7d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8";
+
+    const CGROUP_V1: &str = "\
+12:memory:/docker/3f2a9c1b7d4e
+11:cpu,cpuacct:/docker/3f2a9c1b7d4e
+1:name=systemd:/system.slice/containerd.service
+";
+
+    fn container(runtime: Runtime) -> Option<CgroupOwner> {
+        Some(CgroupOwner::Container {
+            runtime,
+            id: ID.into(),
+        })
+    }
+
+    #[test]
+    fn test_cgroup_path() -> AppResult<()> {
+        assert_eq!(
+            Some("/system.slice/sshd.service"),
+            cgroup_path("0::/system.slice/sshd.service\n")
+        );
+        assert_eq!(
+            Some("/system.slice/containerd.service"),
+            cgroup_path(CGROUP_V1)
+        );
+        assert_eq!(None, cgroup_path(""));
+        assert_eq!(
+            Some("/user.slice"),
+            unified_path("1:name=systemd:/\n0::/user.slice\n")
+        );
+        assert_eq!(None, unified_path(CGROUP_V1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_unit() -> AppResult<()> {
+        assert_eq!(
+            Some(CgroupOwner::Unit("sshd.service".into())),
+            parse_owner("/system.slice/sshd.service")
+        );
+        assert_eq!(
+            Some(CgroupOwner::Unit("session-2.scope".into())),
+            parse_owner("/user.slice/user-1000.slice/session-2.scope")
+        );
+        assert_eq!(None, parse_owner("/"));
+        assert_eq!(None, parse_owner("/init.scope.d"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_container() -> AppResult<()> {
+        assert_eq!(
+            container(Runtime::Docker),
+            parse_owner(&format!("/system.slice/docker-{ID}.scope"))
+        );
+        assert_eq!(container(Runtime::Docker), parse_owner(&format!("/docker/{ID}")));
+        assert_eq!(
+            container(Runtime::Podman),
+            parse_owner(&format!(
+                "/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-{ID}.scope/container"
+            ))
+        );
+        assert_eq!(
+            container(Runtime::Podman),
+            parse_owner(&format!("/machine.slice/libpod-conmon-{ID}.scope"))
+        );
+        // IDが途中で切れているものはコンテナとみなさない
+        assert_eq!(
+            Some(CgroupOwner::Unit("docker-3f2a.scope".into())),
+            parse_owner("/system.slice/docker-3f2a.scope")
+        );
+        assert_eq!(
+            Some("docker:3f2a9c1b7d4e".to_owned()),
+            container(Runtime::Docker).map(|owner| owner.to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_pod() -> AppResult<()> {
+        let uid = "0b6f9d3e-8a51-4c8e-9b1a-2f3c4d5e6f70";
+        let systemd = format!(
+            "/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod{}.slice/cri-containerd-{ID}.scope",
+            uid.replace('-', "_")
+        );
+        let expected = Some(CgroupOwner::Pod {
+            uid: uid.into(),
+            container: Some(ID.into()),
+        });
+        assert_eq!(expected, parse_owner(&systemd));
+        assert_eq!(
+            expected,
+            parse_owner(&format!("/kubepods/besteffort/pod{uid}/{ID}"))
+        );
+        // pauseコンテナより上のPodのスライス自体
+        assert_eq!(
+            Some(CgroupOwner::Pod {
+                uid: uid.into(),
+                container: None,
+            }),
+            parse_owner(&format!("/kubepods/pod{uid}"))
+        );
+        assert_eq!(
+            Some("pod:0b6f9d3e-8a5".to_owned()),
+            expected.map(|owner| owner.to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_owner() -> AppResult<()> {
+        let root = TempDir::new("cgroup")?;
+        std::fs::create_dir_all(root.join("42"))?;
+        std::fs::write(
+            root.join("42").join("cgroup"),
+            format!("0::/system.slice/docker-{ID}.scope\n"),
+        )?;
+
+        let owner = read_owner(&root, sysinfo::Pid::from_u32(42));
+        let missing = read_owner(&root, sysinfo::Pid::from_u32(43));
+
+        assert_eq!(container(Runtime::Docker), owner);
+        assert_eq!(None, missing);
+        assert_eq!(None, read_owner(Path::new("/nonexistent"), sysinfo::Pid::from_u32(1)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_owner_cache() -> AppResult<()> {
+        let mut cache = OwnerCache::new(Duration::from_secs(5));
+        let owners = RefCell::new(HashMap::from([(1, "a.service"), (2, "b.service"), (3, "c.service")]));
+        let reads = Cell::new(0);
+        let read = |pid: Pid| {
+            reads.set(reads.get() + 1);
+            owners
+                .borrow()
+                .get(&pid.as_u32())
+                .map(|unit| CgroupOwner::Unit(unit.to_string()))
+        };
+        let pid = Pid::from_u32;
+        let now = Instant::now();
+        let later = |secs| now + Duration::from_secs(secs);
+
+        cache.refresh([(pid(1), 10), (pid(2), 20)], now, read);
+        cache.refresh([(pid(1), 10), (pid(2), 20)], later(1), read);
+        assert_eq!(2, reads.get());
+        // PIDが再利用されたら読み直し、消えたものは忘れる
+        cache.refresh([(pid(1), 10), (pid(2), 30), (pid(3), 30)], later(2), read);
+        cache.refresh([(pid(2), 30)], later(3), read);
+        assert_eq!(4, reads.get());
+        assert_eq!(1, cache.len());
+        assert_eq!(Some(CgroupOwner::Unit("b.service".into())), cache.get(pid(2), 30));
+        assert_eq!(None, cache.get(pid(2), 20));
+
+        // 起動時刻が同じままcgroupを移ったものはttlが過ぎたら追いつく
+        owners.borrow_mut().insert(2, "run-u42.scope");
+        cache.refresh([(pid(2), 30)], later(4), read);
+        assert_eq!(Some(CgroupOwner::Unit("b.service".into())), cache.get(pid(2), 30));
+        cache.refresh([(pid(2), 30)], later(7), read);
+        assert_eq!(Some(CgroupOwner::Unit("run-u42.scope".into())), cache.get(pid(2), 30));
+
+        // 読めなかったものも読み直す
+        owners.borrow_mut().remove(&2);
+        cache.refresh([(pid(2), 30)], later(12), read);
+        assert_eq!(None, cache.get(pid(2), 30));
+        owners.borrow_mut().insert(2, "b.service");
+        cache.refresh([(pid(2), 30)], later(17), read);
+        assert_eq!(Some(CgroupOwner::Unit("b.service".into())), cache.get(pid(2), 30));
+        Ok(())
+    }
+}
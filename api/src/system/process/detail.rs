@@ -0,0 +1,330 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use shared::error::AppResult;
+use sysinfo::Pid;
+
+use crate::system::{
+    pressure::{PressureSnapshot, CGROUP_ROOT},
+    process::cgroup::unified_path,
+    socket::{process_sockets, SocketEntry},
+};
+
+/// /proc/<pid>/statusのうち詳細画面に出す項目
+const STATUS_FIELDS: [&str; 16] = [
+    "Name",
+    "State",
+    "Tgid",
+    "PPid",
+    "TracerPid",
+    "Uid",
+    "Gid",
+    "Threads",
+    "VmPeak",
+    "VmSize",
+    "VmHWM",
+    "VmRSS",
+    "VmSwap",
+    "voluntary_ctxt_switches",
+    "nonvoluntary_ctxt_switches",
+    "Cpus_allowed_list",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceLimit {
+    pub name: String,
+    pub soft: String,
+    pub hard: String,
+    pub units: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenFile {
+    pub fd: u32,
+    pub target: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MapsSummary {
+    pub mappings: usize,
+    pub total_size: u64,
+    pub anon_size: u64,
+    pub file_size: u64,
+    /// サイズの大きい順のファイル
+    pub largest_files: Vec<(String, u64)>,
+}
+
+/// 詳細画面用にその場で/procから読んだプロセス情報。
+/// 権限がなく読めなかった項目は`None`
+#[derive(Debug, Clone)]
+pub struct ProcessDetail {
+    pub pid: Pid,
+    pub cmdline: Option<Vec<String>>,
+    pub exe: Option<PathBuf>,
+    pub cwd: Option<PathBuf>,
+    pub environ: Option<Vec<String>>,
+    pub status: Option<Vec<(String, String)>>,
+    pub limits: Option<Vec<ResourceLimit>>,
+    pub open_files: Option<Vec<OpenFile>>,
+    pub maps: Option<MapsSummary>,
+    pub sockets: Option<Vec<SocketEntry>>,
+    /// 所属するcgroup v2のPSI
+    pub pressure: Option<PressureSnapshot>,
+}
+
+impl ProcessDetail {
+    const LARGEST_FILES: usize = 5;
+
+    /// `proc_root`は通常`/proc`。テストでは差し替える
+    pub fn collect(proc_root: &Path, pid: Pid) -> AppResult<Self> {
+        let dir = proc_root.join(pid.to_string());
+        // プロセスが消えている場合だけはエラーにする
+        fs::metadata(&dir)?;
+
+        Ok(Self {
+            pid,
+            cmdline: fs::read(dir.join("cmdline")).ok().map(|b| split_nul(&b)),
+            exe: fs::read_link(dir.join("exe")).ok(),
+            cwd: fs::read_link(dir.join("cwd")).ok(),
+            environ: fs::read(dir.join("environ")).ok().map(|b| split_nul(&b)),
+            status: fs::read_to_string(dir.join("status"))
+                .ok()
+                .map(|s| parse_status(&s)),
+            limits: fs::read_to_string(dir.join("limits"))
+                .ok()
+                .map(|s| parse_limits(&s)),
+            open_files: read_open_files(&dir.join("fd")),
+            maps: fs::read_to_string(dir.join("maps"))
+                .ok()
+                .map(|s| summarize_maps(&s, Self::LARGEST_FILES)),
+            sockets: process_sockets(proc_root, pid),
+            pressure: fs::read_to_string(dir.join("cgroup"))
+                .ok()
+                .and_then(|content| read_cgroup_pressure(Path::new(CGROUP_ROOT), &content)),
+        })
+    }
+
+    pub fn get_status(&self, key: &str) -> Option<&str> {
+        self.status
+            .iter()
+            .flatten()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn get_threads(&self) -> Option<usize> {
+        self.get_status("Threads").and_then(|v| v.parse().ok())
+    }
+}
+
+/// hybrid構成ではv2の階層が`unified`の下にある
+fn read_cgroup_pressure(cgroup_root: &Path, content: &str) -> Option<PressureSnapshot> {
+    let path = unified_path(content)?;
+    [cgroup_root.to_path_buf(), cgroup_root.join("unified")]
+        .iter()
+        .find_map(|root| PressureSnapshot::read_cgroup(root, path).ok())
+}
+
+fn split_nul(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .split(|b| *b == 0)
+        .filter(|s| !s.is_empty())
+        .map(|s| String::from_utf8_lossy(s).into_owned())
+        .collect()
+}
+
+fn read_open_files(fd_dir: &Path) -> Option<Vec<OpenFile>> {
+    let mut files = fs::read_dir(fd_dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let fd = entry.file_name().to_str()?.parse().ok()?;
+            // 読んでいる間に閉じられたfdは飛ばす
+            let target = fs::read_link(entry.path()).ok()?;
+            Some(OpenFile {
+                fd,
+                target: target.display().to_string(),
+            })
+        })
+        .collect::<Vec<_>>();
+    files.sort_by_key(|file| file.fd);
+    Some(files)
+}
+
+pub fn parse_status(content: &str) -> Vec<(String, String)> {
+    let fields = content
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim(), v.split_whitespace().collect::<Vec<_>>().join(" ")))
+        .collect::<HashMap<_, _>>();
+
+    STATUS_FIELDS
+        .iter()
+        .filter_map(|key| fields.get(key).map(|v| (key.to_string(), v.clone())))
+        .collect()
+}
+
+/// 列の位置はヘッダーの`Soft Limit`などの開始位置で決まる
+pub fn parse_limits(content: &str) -> Vec<ResourceLimit> {
+    let mut lines = content.lines();
+    let Some(header) = lines.next() else {
+        return vec![];
+    };
+    let (Some(soft), Some(hard), Some(units)) = (
+        header.find("Soft Limit"),
+        header.find("Hard Limit"),
+        header.find("Units"),
+    ) else {
+        return vec![];
+    };
+
+    let column = |line: &str, start: usize, end: usize| {
+        line.get(start..end.min(line.len()))
+            .unwrap_or_default()
+            .trim()
+            .to_owned()
+    };
+    lines
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| ResourceLimit {
+            name: column(line, 0, soft),
+            soft: column(line, soft, hard),
+            hard: column(line, hard, units),
+            units: column(line, units, line.len()),
+        })
+        .collect()
+}
+
+pub fn summarize_maps(content: &str, largest: usize) -> MapsSummary {
+    let mut summary = MapsSummary::default();
+    let mut files: HashMap<&str, u64> = HashMap::new();
+
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let Some((start, end)) = fields.next().and_then(|range| range.split_once('-')) else {
+            continue;
+        };
+        let (Ok(start), Ok(end)) = (
+            u64::from_str_radix(start, 16),
+            u64::from_str_radix(end, 16),
+        ) else {
+            continue;
+        };
+        let size = end.saturating_sub(start);
+        // perms offset dev inodeの後がパス
+        let path = fields.nth(4);
+
+        summary.mappings += 1;
+        summary.total_size += size;
+        match path {
+            Some(path) if path.starts_with('/') => {
+                summary.file_size += size;
+                *files.entry(path).or_default() += size;
+            }
+            _ => summary.anon_size += size,
+        }
+    }
+
+    let mut files = files
+        .into_iter()
+        .map(|(path, size)| (path.to_owned(), size))
+        .collect::<Vec<_>>();
+    files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    files.truncate(largest);
+    summary.largest_files = files;
+    summary
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use shared::error::AppResult;
+    use sysinfo::Pid;
+
+    use super::{parse_limits, parse_status, split_nul, summarize_maps, ProcessDetail};
+
+    const LIMITS: &str = "\
+Limit                     Soft Limit           Hard Limit           Units
+Max cpu time              unlimited            unlimited            seconds
+Max open files            1024                 524288               files
+Max realtime timeout      unlimited            unlimited            us
+";
+
+    const MAPS: &str = "\
+555e0f760000-555e0f762000 r--p 00000000 fe:00 317563                     /usr/bin/cat
+555e0f762000-555e0f767000 r-xp 00002000 fe:00 317563                     /usr/bin/cat
+555e10000000-555e10021000 rw-p 00000000 00:00 0                          [heap]
+7f0000000000-7f0000001000 rw-p 00000000 00:00 0
+7f0000001000-7f0000003000 r--p 00000000 fe:00 1234                       /usr/lib/libc.so.6
+";
+
+    #[test]
+    fn test_parse_limits() -> AppResult<()> {
+        let limits = parse_limits(LIMITS);
+        assert_eq!(3, limits.len());
+        assert_eq!("Max open files", limits[1].name);
+        assert_eq!("1024", limits[1].soft);
+        assert_eq!("524288", limits[1].hard);
+        assert_eq!("files", limits[1].units);
+        assert_eq!("us", limits[2].units);
+        assert!(parse_limits("").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_status() -> AppResult<()> {
+        let status = parse_status("Name:\tcat\nUmask:\t0022\nState:\tR (running)\nThreads:\t1\nVmRSS:\t    1024 kB\n");
+        assert_eq!(
+            vec![
+                ("Name".to_owned(), "cat".to_owned()),
+                ("State".to_owned(), "R (running)".to_owned()),
+                ("Threads".to_owned(), "1".to_owned()),
+                ("VmRSS".to_owned(), "1024 kB".to_owned()),
+            ],
+            status
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_summarize_maps() -> AppResult<()> {
+        let summary = summarize_maps(MAPS, 1);
+        assert_eq!(5, summary.mappings);
+        assert_eq!(0x7000 + 0x2000, summary.file_size);
+        assert_eq!(0x21000 + 0x1000, summary.anon_size);
+        assert_eq!(summary.total_size, summary.file_size + summary.anon_size);
+        assert_eq!(vec![("/usr/bin/cat".to_owned(), 0x7000)], summary.largest_files);
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_nul() -> AppResult<()> {
+        assert_eq!(
+            vec!["sleep".to_owned(), "30".to_owned()],
+            split_nul(b"sleep\x0030\x00")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_collect_self() -> AppResult<()> {
+        let pid = sysinfo::get_current_pid().unwrap();
+        let detail = ProcessDetail::collect(Path::new("/proc"), pid)?;
+
+        assert!(detail.get_threads().is_some_and(|threads| threads > 0));
+        assert!(detail.cmdline.is_some_and(|cmd| !cmd.is_empty()));
+        assert!(detail.exe.is_some());
+        assert!(detail.cwd.is_some());
+        assert!(detail.open_files.is_some_and(|files| !files.is_empty()));
+        assert!(detail.limits.is_some_and(|limits| !limits.is_empty()));
+        assert!(detail.maps.is_some_and(|maps| maps.mappings > 0));
+        assert!(detail.sockets.is_some());
+
+        assert!(ProcessDetail::collect(Path::new("/proc"), Pid::from_u32(0)).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,371 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use shared::util::DisplayDuration;
+use strum::Display;
+use sysinfo::{Pid, ProcessStatus};
+
+use crate::system::oom::OomKill;
+
+use super::{cgroup::parse_owner, signal::ProcessSignal, ProcessRow, ProcessSnapshot};
+
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    #[strum(to_string = "START")]
+    Started,
+    #[strum(to_string = "EXIT")]
+    Exited,
+    #[strum(to_string = "OOM")]
+    OomKill,
+}
+
+/// 終了理由。終了コードは親が回収してしまうので分かる範囲だけ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCause {
+    /// ゾンビになっていたのを親が回収した
+    Reaped,
+    /// このアプリから送ったシグナルの後に消えた
+    Signaled(ProcessSignal),
+    /// 同じ更新でOOM killが起きていて、一番メモリを使っていた
+    OomKilled,
+}
+
+impl fmt::Display for ExitCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Reaped => write!(f, "reaped zombie"),
+            Self::Signaled(signal) => write!(f, "after {signal}"),
+            Self::OomKilled => write!(f, "oom killed"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProcessEvent {
+    /// 変化を見つけたスナップショットの時刻
+    pub at: SystemTime,
+    pub kind: EventKind,
+    pub pid: Pid,
+    pub name: String,
+    pub user: String,
+    pub cmd: String,
+    /// 開始なら見つけた時点の経過時間、終了なら最後に見えた時点までの実行時間
+    pub runtime: Option<Duration>,
+    pub cause: Option<ExitCause>,
+    /// OOM killが起きたcgroup
+    pub cgroup: Option<String>,
+}
+
+impl ProcessEvent {
+    /// `seen_at`はこのプロセスが見えていた最後の時刻
+    fn new(kind: EventKind, row: &ProcessRow, at: SystemTime, seen_at: SystemTime) -> Self {
+        let started = UNIX_EPOCH + Duration::from_secs(row.start_time);
+        Self {
+            at,
+            kind,
+            pid: row.pid,
+            name: row.name.clone(),
+            user: row.user.clone(),
+            cmd: row.cmd.clone(),
+            runtime: (row.start_time > 0)
+                .then(|| seen_at.duration_since(started).ok())
+                .flatten(),
+            cause: None,
+            cgroup: None,
+        }
+    }
+
+    /// 犠牲になったプロセスが分からなければPIDは0
+    fn oom_kill(at: SystemTime, victim: Option<&ProcessEvent>, cgroup: Option<String>) -> Self {
+        Self {
+            at,
+            kind: EventKind::OomKill,
+            pid: victim.map(|victim| victim.pid).unwrap_or(Pid::from_u32(0)),
+            name: victim.map(|victim| victim.name.clone()).unwrap_or_default(),
+            user: victim.map(|victim| victim.user.clone()).unwrap_or_default(),
+            cmd: victim.map(|victim| victim.cmd.clone()).unwrap_or_default(),
+            runtime: victim.and_then(|victim| victim.runtime),
+            cause: None,
+            cgroup,
+        }
+    }
+}
+
+impl fmt::Display for ProcessEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.kind == EventKind::OomKill {
+            write!(f, "OOM kill in {}", self.cgroup.as_deref().unwrap_or("unknown cgroup"))?;
+            if self.pid.as_u32() != 0 {
+                write!(f, " victim {} ({}): {}", self.name, self.pid, self.cmd)?;
+            }
+            return Ok(());
+        }
+        write!(f, "process {} {} ({})", self.kind, self.name, self.pid)?;
+        if let Some(runtime) = self.runtime {
+            write!(f, " runtime {}", DisplayDuration::new(runtime))?;
+        }
+        if let Some(cause) = self.cause {
+            write!(f, " {cause}")?;
+        }
+        write!(f, ": {}", self.cmd)
+    }
+}
+
+/// 2つのスナップショットの差分から起動・終了を拾う。
+/// 同じPIDでも起動時刻が変わっていれば再利用されたとみなして終了と起動の両方を出す
+pub fn diff_snapshots(prev: &ProcessSnapshot, next: &ProcessSnapshot) -> Vec<ProcessEvent> {
+    // 起動直後は全プロセスが新規に見えるので比べない
+    if prev.is_empty() {
+        return vec![];
+    }
+    let prev_at = prev.get_taken_at();
+    let next_at = next.get_taken_at();
+
+    let mut exited = prev
+        .get_processes()
+        .values()
+        .filter(|row| {
+            next.get(&row.pid)
+                .is_none_or(|next_row| next_row.start_time != row.start_time)
+        })
+        .map(|row| {
+            let mut event = ProcessEvent::new(EventKind::Exited, row, next_at, prev_at);
+            if row.status == ProcessStatus::Zombie {
+                event.cause = Some(ExitCause::Reaped);
+            }
+            event
+        })
+        .collect::<Vec<_>>();
+    exited.sort_by_key(|event| event.pid);
+
+    let mut started = next
+        .get_processes()
+        .values()
+        .filter(|row| {
+            prev.get(&row.pid)
+                .is_none_or(|prev_row| prev_row.start_time != row.start_time)
+        })
+        .map(|row| ProcessEvent::new(EventKind::Started, row, next_at, next_at))
+        .collect::<Vec<_>>();
+    started.sort_by_key(|event| event.pid);
+
+    exited.extend(started);
+    exited
+}
+
+/// OOM killごとにイベントを作る。犠牲は同じ更新で消えたプロセスのうち
+/// 同じcgroupのもの(なければ全体)から、OOM killerと同じくRSSが一番大きいものを選ぶ
+pub fn oom_events(
+    prev: &ProcessSnapshot,
+    next: &ProcessSnapshot,
+    events: &mut [ProcessEvent],
+    kills: &[OomKill],
+) -> Vec<ProcessEvent> {
+    let rss = |event: &ProcessEvent| prev.get(&event.pid).map(|row| row.rss).unwrap_or_default();
+    kills
+        .iter()
+        .map(|kill| {
+            let owner = kill.cgroup.as_deref().and_then(parse_owner);
+            let candidates = || {
+                events
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, event)| event.kind == EventKind::Exited && event.cause.is_none())
+            };
+            let same_cgroup = candidates()
+                .filter(|(_, event)| {
+                    owner.is_some()
+                        && prev.get(&event.pid).and_then(|row| row.cgroup.as_ref()) == owner.as_ref()
+                })
+                .max_by_key(|(_, event)| rss(event))
+                .map(|(idx, _)| idx);
+            let victim = same_cgroup
+                .or_else(|| candidates().max_by_key(|(_, event)| rss(event)).map(|(idx, _)| idx));
+            if let Some(idx) = victim {
+                events[idx].cause = Some(ExitCause::OomKilled);
+            }
+            ProcessEvent::oom_kill(
+                next.get_taken_at(),
+                victim.map(|idx| &events[idx]),
+                kill.cgroup.clone(),
+            )
+        })
+        .collect()
+}
+
+/// 古いものから捨てる固定長のイベント履歴
+#[derive(Debug)]
+pub struct EventLog {
+    events: VecDeque<ProcessEvent>,
+    capacity: usize,
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl EventLog {
+    const DEFAULT_CAPACITY: usize = 1000;
+
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, event: ProcessEvent) {
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// 新しい順
+    pub fn iter(&self) -> impl Iterator<Item = &ProcessEvent> {
+        self.events.iter().rev()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashMap,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use shared::error::AppResult;
+    use sysinfo::{Pid, ProcessStatus};
+
+    use crate::system::{
+        oom::OomKill,
+        process::{cgroup::parse_owner, test::row, ProcessRow, ProcessSnapshot},
+    };
+
+    use super::{diff_snapshots, oom_events, EventKind, EventLog, ExitCause};
+
+    fn snapshot(secs: u64, rows: Vec<ProcessRow>) -> ProcessSnapshot {
+        ProcessSnapshot::new(
+            UNIX_EPOCH + Duration::from_secs(secs),
+            rows.into_iter().map(|row| (row.pid, row)).collect::<HashMap<_, _>>(),
+        )
+    }
+
+    fn started(pid: u32, name: &str, start_time: u64) -> ProcessRow {
+        let mut row = row(pid, name, 0., 100);
+        row.start_time = start_time;
+        row
+    }
+
+    #[test]
+    fn test_diff_snapshots() -> AppResult<()> {
+        let mut zombie = started(3, "defunct", 50);
+        zombie.status = ProcessStatus::Zombie;
+        let prev = snapshot(
+            100,
+            vec![started(1, "init", 1), started(2, "make", 40), zombie],
+        );
+        let next = snapshot(110, vec![started(1, "init", 1), started(4, "cc1", 105)]);
+
+        let events = diff_snapshots(&prev, &next);
+        let summary = events
+            .iter()
+            .map(|e| (e.kind, e.pid.as_u32()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (EventKind::Exited, 2),
+                (EventKind::Exited, 3),
+                (EventKind::Started, 4)
+            ],
+            summary
+        );
+        assert_eq!(Some(Duration::from_secs(60)), events[0].runtime);
+        assert_eq!(None, events[0].cause);
+        assert_eq!(Some(ExitCause::Reaped), events[1].cause);
+        assert_eq!(Some(Duration::from_secs(5)), events[2].runtime);
+        assert_eq!(UNIX_EPOCH + Duration::from_secs(110), events[2].at);
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_snapshots_reused_pid() -> AppResult<()> {
+        let prev = snapshot(100, vec![started(7, "sh", 90)]);
+        let next = snapshot(101, vec![started(7, "sleep", 100)]);
+
+        let events = diff_snapshots(&prev, &next);
+        assert_eq!(2, events.len());
+        assert_eq!((EventKind::Exited, "sh"), (events[0].kind, events[0].name.as_str()));
+        assert_eq!((EventKind::Started, "sleep"), (events[1].kind, events[1].name.as_str()));
+
+        // 初回は比べない
+        assert!(diff_snapshots(&ProcessSnapshot::default(), &next).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_oom_events() -> AppResult<()> {
+        let mut db = started(2, "postgres", 10);
+        db.rss = 100;
+        db.cgroup = parse_owner("/system.slice/db.service");
+        let mut big = started(3, "java", 10);
+        big.rss = 1000;
+        let prev = snapshot(100, vec![started(1, "init", 1), db, big, started(4, "sh", 10)]);
+        let next = snapshot(101, vec![started(1, "init", 1)]);
+        let mut events = diff_snapshots(&prev, &next);
+        let kills = [
+            OomKill {
+                cgroup: Some("/system.slice/db.service".into()),
+            },
+            OomKill { cgroup: None },
+            OomKill { cgroup: None },
+        ];
+
+        let oom = oom_events(&prev, &next, &mut events, &kills);
+        // cgroupが合うものを先に、残りはRSSの大きい順
+        assert_eq!(
+            vec![2, 3, 4],
+            oom.iter().map(|e| e.pid.as_u32()).collect::<Vec<_>>()
+        );
+        assert!(oom.iter().all(|e| e.kind == EventKind::OomKill));
+        assert_eq!(Some("/system.slice/db.service"), oom[0].cgroup.as_deref());
+        assert!(events.iter().all(|e| e.cause == Some(ExitCause::OomKilled)));
+
+        // 消えたプロセスがなければ犠牲は分からない
+        let oom = oom_events(&next, &next, &mut [], &kills[..1]);
+        assert_eq!(0, oom[0].pid.as_u32());
+        assert_eq!("OOM kill in /system.slice/db.service", oom[0].to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_event_log() -> AppResult<()> {
+        let prev = snapshot(100, vec![started(1, "a", 1)]);
+        let next = snapshot(
+            101,
+            vec![started(2, "b", 100), started(3, "c", 100), started(4, "d", 100)],
+        );
+        let mut log = EventLog::new(3);
+        diff_snapshots(&prev, &next)
+            .into_iter()
+            .for_each(|event| log.push(event));
+
+        assert_eq!(3, log.len());
+        let pids = log.iter().map(|e| e.pid).collect::<Vec<_>>();
+        assert_eq!(vec![Pid::from_u32(4), Pid::from_u32(3), Pid::from_u32(2)], pids);
+        Ok(())
+    }
+}
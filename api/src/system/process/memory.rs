@@ -0,0 +1,249 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use sysinfo::Pid;
+
+use super::ProcessRow;
+
+/// 共有ライブラリを按分したPSSと、そのプロセスだけが持つUSS
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryBreakdown {
+    pub rss: u64,
+    pub pss: u64,
+    pub uss: u64,
+    pub swap: u64,
+}
+
+/// `Rss:  1024 kB`のような行をバイト数で読む
+fn parse_kb_fields(content: &str) -> HashMap<&str, u64> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let kb = value.split_whitespace().next()?.parse::<u64>().ok()?;
+            Some((key.trim(), kb * 1024))
+        })
+        .collect()
+}
+
+/// `/proc/<pid>/smaps_rollup`を読む。スワップは`status`のVmSwapで上書きする
+pub fn parse_smaps_rollup(content: &str) -> Option<MemoryBreakdown> {
+    let fields = parse_kb_fields(content);
+    let private = |key| fields.get(key).copied().unwrap_or_default();
+    Some(MemoryBreakdown {
+        rss: *fields.get("Rss")?,
+        pss: *fields.get("Pss")?,
+        uss: private("Private_Clean") + private("Private_Dirty"),
+        swap: private("Swap"),
+    })
+}
+
+pub fn parse_vm_swap(status: &str) -> Option<u64> {
+    parse_kb_fields(status).get("VmSwap").copied()
+}
+
+/// カーネルスレッドや権限のないプロセスは`None`
+pub fn read_breakdown(proc_root: &Path, pid: Pid) -> Option<MemoryBreakdown> {
+    let dir = proc_root.join(pid.to_string());
+    let mut breakdown = parse_smaps_rollup(&fs::read_to_string(dir.join("smaps_rollup")).ok()?)?;
+    if let Some(swap) = fs::read_to_string(dir.join("status"))
+        .ok()
+        .and_then(|status| parse_vm_swap(&status))
+    {
+        breakdown.swap = swap;
+    }
+    Some(breakdown)
+}
+
+/// PSSなどの列を出したり並べ替えたりする時に毎回読まないよう、読んだ値を(PID, 起動時刻)ごとに覚えておく。
+/// 1回の更新で読むのは未読のものと古くなったものから`budget`件まで
+#[derive(Debug)]
+pub struct MemoryCache {
+    entries: HashMap<Pid, CacheEntry>,
+    ttl: Duration,
+    budget: usize,
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    start_time: u64,
+    read_at: Instant,
+    breakdown: Option<MemoryBreakdown>,
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TTL, Self::DEFAULT_BUDGET)
+    }
+}
+
+impl MemoryCache {
+    const DEFAULT_TTL: Duration = Duration::from_secs(5);
+    const DEFAULT_BUDGET: usize = 32;
+
+    pub fn new(ttl: Duration, budget: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            ttl,
+            budget,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// `rows`に覚えている値を入れる。`rows`にないプロセスは忘れる
+    pub fn fill(
+        &mut self,
+        rows: &mut [ProcessRow],
+        now: Instant,
+        mut read: impl FnMut(Pid) -> Option<MemoryBreakdown>,
+    ) {
+        let start_times = rows
+            .iter()
+            .map(|row| (row.pid, row.start_time))
+            .collect::<HashMap<_, _>>();
+        self.entries
+            .retain(|pid, entry| start_times.get(pid) == Some(&entry.start_time));
+
+        // 読んだことのないものを先に、あとは古い順
+        let mut stale = rows
+            .iter()
+            .filter_map(|row| match self.entries.get(&row.pid) {
+                None => Some((None, row.pid, row.start_time)),
+                Some(entry) if now.duration_since(entry.read_at) >= self.ttl => {
+                    Some((Some(entry.read_at), row.pid, row.start_time))
+                }
+                Some(_) => None,
+            })
+            .collect::<Vec<_>>();
+        stale.sort_by_key(|(read_at, _, _)| *read_at);
+        for (_, pid, start_time) in stale.into_iter().take(self.budget) {
+            let entry = CacheEntry {
+                start_time,
+                read_at: now,
+                breakdown: read(pid),
+            };
+            self.entries.insert(pid, entry);
+        }
+
+        for row in rows.iter_mut() {
+            row.memory = self.entries.get(&row.pid).and_then(|entry| entry.breakdown);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use shared::error::AppResult;
+
+    use std::{
+        cell::RefCell,
+        time::{Duration, Instant},
+    };
+
+    use sysinfo::Pid;
+
+    use crate::system::process::test::row;
+
+    use super::{parse_smaps_rollup, parse_vm_swap, read_breakdown, MemoryBreakdown, MemoryCache};
+
+    const SMAPS_ROLLUP: &str = "\
+55d0f1a3c000-7ffd2b5fe000 ---p 00000000 00:00 0                          [rollup]
+Rss:                8192 kB
+Pss:                3072 kB
+Pss_Anon:           1024 kB
+Shared_Clean:       5120 kB
+Shared_Dirty:          0 kB
+Private_Clean:       512 kB
+Private_Dirty:      2560 kB
+Referenced:         8192 kB
+Anonymous:          2048 kB
+Swap:                 64 kB
+SwapPss:              64 kB
+";
+
+    #[test]
+    fn test_parse_smaps_rollup() -> AppResult<()> {
+        assert_eq!(
+            Some(MemoryBreakdown {
+                rss: 8192 * 1024,
+                pss: 3072 * 1024,
+                uss: 3072 * 1024,
+                swap: 64 * 1024,
+            }),
+            parse_smaps_rollup(SMAPS_ROLLUP)
+        );
+        assert_eq!(None, parse_smaps_rollup(""));
+        assert_eq!(
+            Some(128 * 1024),
+            parse_vm_swap("Name:\tcat\nVmRSS:\t  1024 kB\nVmSwap:\t   128 kB\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_cache() -> AppResult<()> {
+        let mut rows = (1..=10)
+            .map(|pid| {
+                let mut row = row(pid, "worker", 0., 100);
+                row.start_time = 100;
+                row
+            })
+            .collect::<Vec<_>>();
+        let mut cache = MemoryCache::new(Duration::from_secs(5), 4);
+        let now = Instant::now();
+        let reads = RefCell::new(vec![]);
+        let read = |pid: Pid| {
+            reads.borrow_mut().push(pid.as_u32());
+            Some(MemoryBreakdown {
+                pss: pid.as_u32() as u64,
+                ..Default::default()
+            })
+        };
+
+        // 並べ替えでも1回の更新で全行は読まない
+        cache.fill(&mut rows, now, read);
+        assert_eq!(4, reads.borrow().len());
+        assert_eq!(4, rows.iter().filter(|row| row.memory.is_some()).count());
+        cache.fill(&mut rows, now, read);
+        cache.fill(&mut rows, now, read);
+        assert_eq!(10, reads.borrow().len());
+        assert!(rows.iter().all(|row| row.memory.is_some()));
+
+        // 期限内は読み直さず、PIDが再利用されたものだけ読む
+        reads.borrow_mut().clear();
+        rows[0].start_time = 200;
+        cache.fill(&mut rows, now + Duration::from_secs(1), read);
+        assert_eq!(vec![1], *reads.borrow());
+
+        // 期限が切れたら古いものから読み直し、消えたプロセスは忘れる
+        reads.borrow_mut().clear();
+        rows.truncate(6);
+        cache.fill(&mut rows, now + Duration::from_secs(5), read);
+        assert_eq!(4, reads.borrow().len());
+        assert!(!reads.borrow().contains(&1));
+        assert_eq!(6, cache.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_breakdown_self() -> AppResult<()> {
+        let pid = sysinfo::get_current_pid().unwrap();
+        let breakdown = read_breakdown(Path::new("/proc"), pid);
+
+        assert!(breakdown.is_some_and(|b| b.rss > 0 && b.pss > 0 && b.uss <= b.rss));
+        Ok(())
+    }
+}
@@ -0,0 +1,784 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    path::Path,
+    time::{Instant, SystemTime},
+};
+
+use regex::{Regex, RegexBuilder};
+use shared::{
+    error::AppResult,
+    util::{DisplayBytes, DisplayOsStr},
+};
+use strum::{Display, EnumIter, FromRepr};
+use sysinfo::{Pid, Process, ProcessStatus};
+
+use super::{SysInfo, PROC_ROOT, UNKONW};
+
+pub mod aggregate;
+pub mod cgroup;
+pub mod detail;
+pub mod event;
+pub mod memory;
+pub mod signal;
+pub mod tree;
+
+#[derive(Debug, Clone)]
+pub struct ProcessRow {
+    pub pid: Pid,
+    pub ppid: Option<Pid>,
+    pub name: String,
+    pub user: String,
+    pub status: ProcessStatus,
+    pub cpu_usage: f32,
+    pub rss: u64,
+    pub virtual_memory: u64,
+    pub start_time: u64,
+    pub cmd: String,
+    pub disk_read: u64,
+    pub disk_written: u64,
+    /// 毎秒の読み書きバイト数
+    pub read_rate: u64,
+    pub write_rate: u64,
+    pub cgroup: Option<cgroup::CgroupOwner>,
+    /// 表示中の行だけ後から読むので、読んでいなければ`None`
+    pub memory: Option<memory::MemoryBreakdown>,
+}
+
+impl Default for ProcessRow {
+    fn default() -> Self {
+        Self {
+            pid: Pid::from_u32(0),
+            ppid: None,
+            name: String::new(),
+            user: String::new(),
+            status: ProcessStatus::Unknown(0),
+            cpu_usage: 0.,
+            rss: 0,
+            virtual_memory: 0,
+            start_time: 0,
+            cmd: String::new(),
+            disk_read: 0,
+            disk_written: 0,
+            read_rate: 0,
+            write_rate: 0,
+            cgroup: None,
+            memory: None,
+        }
+    }
+}
+
+impl ProcessRow {
+    /// 折りたたみや集計で使用量を足し合わせる
+    pub fn add_usage(&mut self, other: &ProcessRow) {
+        self.cpu_usage += other.cpu_usage;
+        self.rss += other.rss;
+        self.virtual_memory += other.virtual_memory;
+        self.disk_read += other.disk_read;
+        self.disk_written += other.disk_written;
+        self.read_rate += other.read_rate;
+        self.write_rate += other.write_rate;
+    }
+
+    /// systemdのユニットやコンテナ。どこにも属さなければ`-`
+    pub fn cgroup_label(&self) -> String {
+        self.cgroup
+            .as_ref()
+            .map(|owner| owner.to_string())
+            .unwrap_or_else(|| "-".into())
+    }
+
+    /// topと同じ1文字の状態コード
+    pub fn status_code(&self) -> &'static str {
+        match self.status {
+            ProcessStatus::Run => "R",
+            ProcessStatus::Sleep => "S",
+            ProcessStatus::Idle => "I",
+            ProcessStatus::Stop => "T",
+            ProcessStatus::Tracing => "t",
+            ProcessStatus::Zombie => "Z",
+            ProcessStatus::Dead => "X",
+            ProcessStatus::UninterruptibleDiskSleep => "D",
+            ProcessStatus::Wakekill => "K",
+            ProcessStatus::Waking => "W",
+            ProcessStatus::Parked => "P",
+            _ => "?",
+        }
+    }
+}
+
+#[derive(Debug, Default, Display, FromRepr, EnumIter, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessColumn {
+    #[default]
+    #[strum(to_string = "PID")]
+    Pid,
+    #[strum(to_string = "PPID")]
+    Ppid,
+    #[strum(to_string = "USER")]
+    User,
+    #[strum(to_string = "S")]
+    Status,
+    #[strum(to_string = "CPU%")]
+    Cpu,
+    #[strum(to_string = "RSS")]
+    Rss,
+    #[strum(to_string = "PSS")]
+    Pss,
+    #[strum(to_string = "USS")]
+    Uss,
+    #[strum(to_string = "SWAP")]
+    Swap,
+    #[strum(to_string = "VIRT")]
+    Virt,
+    #[strum(to_string = "READ/s")]
+    ReadRate,
+    #[strum(to_string = "WRITE/s")]
+    WriteRate,
+    #[strum(to_string = "READ")]
+    DiskRead,
+    #[strum(to_string = "WRITE")]
+    DiskWrite,
+    #[strum(to_string = "START")]
+    Start,
+    #[strum(to_string = "CGROUP")]
+    Cgroup,
+    #[strum(to_string = "NAME")]
+    Name,
+    #[strum(to_string = "COMMAND")]
+    Command,
+}
+
+impl ProcessColumn {
+    /// smaps_rollupから読む列。読むのが重いので明示的に表示した時だけ出す
+    pub fn is_memory_detail(self) -> bool {
+        matches!(self, Self::Pss | Self::Uss | Self::Swap)
+    }
+
+    pub fn is_numeric(self) -> bool {
+        matches!(
+            self,
+            Self::Pid
+                | Self::Ppid
+                | Self::Cpu
+                | Self::Rss
+                | Self::Pss
+                | Self::Uss
+                | Self::Swap
+                | Self::Virt
+                | Self::ReadRate
+                | Self::WriteRate
+                | Self::DiskRead
+                | Self::DiskWrite
+        )
+    }
+
+    pub fn cell(self, row: &ProcessRow) -> String {
+        match self {
+            Self::Pid => row.pid.to_string(),
+            Self::Ppid => row
+                .ppid
+                .map(|p| p.to_string())
+                .unwrap_or_else(|| "-".into()),
+            Self::User => row.user.clone(),
+            Self::Status => row.status_code().into(),
+            Self::Cpu => format!("{:.1}", row.cpu_usage),
+            Self::Rss => DisplayBytes::new(row.rss).to_string(),
+            Self::Pss | Self::Uss | Self::Swap => self
+                .memory_detail(row)
+                .map(|bytes| DisplayBytes::new(bytes).to_string())
+                .unwrap_or_else(|| "-".into()),
+            Self::Virt => DisplayBytes::new(row.virtual_memory).to_string(),
+            Self::ReadRate => DisplayBytes::new(row.read_rate).to_string(),
+            Self::WriteRate => DisplayBytes::new(row.write_rate).to_string(),
+            Self::DiskRead => DisplayBytes::new(row.disk_read).to_string(),
+            Self::DiskWrite => DisplayBytes::new(row.disk_written).to_string(),
+            Self::Start => format_start_time(row.start_time),
+            Self::Cgroup => row.cgroup_label(),
+            Self::Name => row.name.clone(),
+            Self::Command => row.cmd.clone(),
+        }
+    }
+
+    pub fn compare(self, a: &ProcessRow, b: &ProcessRow) -> Ordering {
+        match self {
+            Self::Pid => a.pid.cmp(&b.pid),
+            Self::Ppid => a.ppid.cmp(&b.ppid),
+            Self::User => a.user.cmp(&b.user),
+            Self::Status => a.status_code().cmp(b.status_code()),
+            Self::Cpu => a.cpu_usage.total_cmp(&b.cpu_usage),
+            Self::Rss => a.rss.cmp(&b.rss),
+            Self::Pss | Self::Uss | Self::Swap => {
+                self.memory_detail(a).cmp(&self.memory_detail(b))
+            }
+            Self::Virt => a.virtual_memory.cmp(&b.virtual_memory),
+            Self::ReadRate => a.read_rate.cmp(&b.read_rate),
+            Self::WriteRate => a.write_rate.cmp(&b.write_rate),
+            Self::DiskRead => a.disk_read.cmp(&b.disk_read),
+            Self::DiskWrite => a.disk_written.cmp(&b.disk_written),
+            Self::Start => a.start_time.cmp(&b.start_time),
+            Self::Cgroup => a.cgroup_label().cmp(&b.cgroup_label()),
+            Self::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            Self::Command => a.cmd.cmp(&b.cmd),
+        }
+    }
+
+    /// 数値系は大きい順の方が見たいことが多い
+    pub fn default_order(self) -> SortOrder {
+        match self {
+            Self::Name
+            | Self::Command
+            | Self::User
+            | Self::Status
+            | Self::Pid
+            | Self::Ppid
+            | Self::Cgroup => SortOrder::Ascending,
+            _ => SortOrder::Descending,
+        }
+    }
+
+    fn memory_detail(self, row: &ProcessRow) -> Option<u64> {
+        let memory = row.memory?;
+        match self {
+            Self::Pss => Some(memory.pss),
+            Self::Uss => Some(memory.uss),
+            Self::Swap => Some(memory.swap),
+            _ => None,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        Self::from_repr(self as usize + 1).unwrap_or(Self::Pid)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Ascending => Self::Descending,
+            Self::Descending => Self::Ascending,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Ascending => "▲",
+            Self::Descending => "▼",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessSort {
+    pub column: ProcessColumn,
+    pub order: SortOrder,
+}
+
+impl ProcessSort {
+    pub fn next_column(&mut self) {
+        self.column = self.column.next();
+        self.order = self.column.default_order();
+    }
+
+    pub fn toggle_order(&mut self) {
+        self.order = self.order.toggle();
+    }
+
+    pub fn compare(&self, a: &ProcessRow, b: &ProcessRow) -> Ordering {
+        let ordering = self.column.compare(a, b);
+        let ordering = match self.order {
+            SortOrder::Ascending => ordering,
+            SortOrder::Descending => ordering.reverse(),
+        };
+        // 同じ値の行が更新のたびに入れ替わらないようPIDで順序を固定する
+        ordering.then_with(|| a.pid.cmp(&b.pid))
+    }
+
+    pub fn apply(&self, rows: &mut [ProcessRow]) {
+        rows.sort_by(|a, b| self.compare(a, b));
+    }
+}
+
+/// 今日起動したものは時刻、それ以前は日付で表示する
+fn format_start_time(start_time: u64) -> String {
+    use chrono::{DateTime, Local};
+
+    let Some(start) = DateTime::from_timestamp(start_time as i64, 0) else {
+        return UNKONW.into();
+    };
+    let start = start.with_timezone(&Local);
+    if start.date_naive() == Local::now().date_naive() {
+        start.format("%H:%M:%S").to_string()
+    } else {
+        start.format("%b%d").to_string()
+    }
+}
+
+#[derive(Debug)]
+enum Matcher {
+    Text(String),
+    Regex(Regex),
+    Invalid(String),
+}
+
+/// 名前・コマンドライン・ユーザー・PIDのいずれかで絞り込む
+#[derive(Debug, Default)]
+pub struct ProcessFilter {
+    query: String,
+    use_regex: bool,
+    ignore_case: bool,
+    matcher: Option<Matcher>,
+}
+
+impl ProcessFilter {
+    pub fn get_query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn is_regex(&self) -> bool {
+        self.use_regex
+    }
+
+    pub fn is_ignore_case(&self) -> bool {
+        self.ignore_case
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.query.is_empty()
+    }
+
+    /// 正規表現が不正な場合のエラー内容
+    pub fn get_error(&self) -> Option<&str> {
+        match &self.matcher {
+            Some(Matcher::Invalid(e)) => Some(e),
+            _ => None,
+        }
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.compile();
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.compile();
+    }
+
+    pub fn clear(&mut self) {
+        self.query.clear();
+        self.compile();
+    }
+
+    pub fn toggle_regex(&mut self) {
+        self.use_regex = !self.use_regex;
+        self.compile();
+    }
+
+    pub fn toggle_ignore_case(&mut self) {
+        self.ignore_case = !self.ignore_case;
+        self.compile();
+    }
+
+    fn compile(&mut self) {
+        self.matcher = if self.query.is_empty() {
+            None
+        } else if self.use_regex {
+            let regex = RegexBuilder::new(&self.query)
+                .case_insensitive(self.ignore_case)
+                .build();
+            Some(match regex {
+                Ok(regex) => Matcher::Regex(regex),
+                Err(e) => Matcher::Invalid(e.to_string()),
+            })
+        } else if self.ignore_case {
+            Some(Matcher::Text(self.query.to_lowercase()))
+        } else {
+            Some(Matcher::Text(self.query.clone()))
+        }
+    }
+
+    pub fn matches(&self, row: &ProcessRow) -> bool {
+        let pid = row.pid.to_string();
+        let cgroup = row.cgroup_label();
+        let fields = [
+            row.name.as_str(),
+            row.cmd.as_str(),
+            row.user.as_str(),
+            &pid,
+            &cgroup,
+        ];
+
+        match &self.matcher {
+            None => true,
+            // 入力途中の不正な正規表現では何も表示しない
+            Some(Matcher::Invalid(_)) => false,
+            Some(Matcher::Regex(regex)) => fields.iter().any(|f| regex.is_match(f)),
+            Some(Matcher::Text(text)) if self.ignore_case => fields
+                .iter()
+                .any(|f| f.to_lowercase().contains(text.as_str())),
+            Some(Matcher::Text(text)) => fields.iter().any(|f| f.contains(text.as_str())),
+        }
+    }
+}
+
+/// ある時点のプロセス一覧。`SysInfo`を借用せずに持ち回せる
+#[derive(Debug, Clone)]
+pub struct ProcessSnapshot {
+    taken_at: SystemTime,
+    processes: HashMap<Pid, ProcessRow>,
+}
+
+impl ProcessSnapshot {
+    pub fn new(taken_at: SystemTime, processes: HashMap<Pid, ProcessRow>) -> Self {
+        Self {
+            taken_at,
+            processes,
+        }
+    }
+
+    pub fn get_taken_at(&self) -> SystemTime {
+        self.taken_at
+    }
+
+    pub fn get(&self, pid: &Pid) -> Option<&ProcessRow> {
+        self.processes.get(pid)
+    }
+
+    pub fn get_processes(&self) -> &HashMap<Pid, ProcessRow> {
+        &self.processes
+    }
+
+    pub fn len(&self) -> usize {
+        self.processes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.processes.is_empty()
+    }
+
+    /// PID順に並べた行
+    pub fn rows(&self) -> Vec<ProcessRow> {
+        let mut rows = self.processes.values().cloned().collect::<Vec<_>>();
+        rows.sort_by_key(|row| row.pid);
+        rows
+    }
+}
+
+impl Default for ProcessSnapshot {
+    fn default() -> Self {
+        ProcessSnapshot::new(SystemTime::UNIX_EPOCH, HashMap::new())
+    }
+}
+
+pub trait SysProcess {
+    fn get_processes(&self) -> Vec<ProcessRow>;
+    fn get_processes_map(&self) -> HashMap<Pid, ProcessRow>;
+    fn get_process_snapshot(&self) -> ProcessSnapshot;
+    fn get_process_detail(&self, pid: Pid) -> AppResult<detail::ProcessDetail>;
+    fn get_process_memory(&self, pid: Pid) -> Option<memory::MemoryBreakdown>;
+}
+
+impl SysInfo {
+    /// 更新のたびに全プロセスの/proc/<pid>/cgroupを開かないよう、新しいプロセスの分だけ読む
+    pub(in crate::system) fn refresh_cgroup_owners(&mut self) {
+        let processes = self
+            .system
+            .processes()
+            .iter()
+            .filter(|(_, process)| process.thread_kind().is_none())
+            .map(|(pid, process)| (*pid, process.start_time()));
+        self.cgroup_owners.refresh(processes, Instant::now(), |pid| {
+            cgroup::read_owner(Path::new(PROC_ROOT), pid)
+        });
+    }
+
+    /// 起動後に追加されたアカウントのプロセスが見えたらユーザー一覧を読み直す。
+    /// 読み直しても見つからないUID(コンテナ内のものなど)は覚えておき、毎回は読み直さない
+    pub(in crate::system) fn refresh_users(&mut self) {
+        let missing = self
+            .system
+            .processes()
+            .values()
+            .filter_map(|process| process.user_id())
+            .filter(|uid| {
+                self.users.get_user_by_id(uid).is_none() && !self.unknown_uids.contains(*uid)
+            })
+            .cloned()
+            .collect::<HashSet<_>>();
+        if missing.is_empty() {
+            return;
+        }
+        self.users.refresh_list();
+        let users = &self.users;
+        self.unknown_uids
+            .extend(missing.into_iter().filter(|uid| users.get_user_by_id(uid).is_none()));
+    }
+
+    fn to_process_row(&self, pid: Pid, process: &Process) -> ProcessRow {
+        let user = process
+            .user_id()
+            .and_then(|uid| self.users.get_user_by_id(uid))
+            .map(|u| u.name().to_owned())
+            .unwrap_or_else(|| UNKONW.into());
+        let disk_usage = process.disk_usage();
+        let cmd = if process.cmd().is_empty() {
+            format!("[{}]", DisplayOsStr::new(process.name()))
+        } else {
+            process
+                .cmd()
+                .iter()
+                .map(|arg| DisplayOsStr::new(arg).to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        ProcessRow {
+            pid,
+            ppid: process.parent(),
+            name: DisplayOsStr::new(process.name()).to_string(),
+            user,
+            status: process.status(),
+            cpu_usage: process.cpu_usage(),
+            rss: process.memory(),
+            virtual_memory: process.virtual_memory(),
+            start_time: process.start_time(),
+            cmd,
+            disk_read: disk_usage.total_read_bytes,
+            disk_written: disk_usage.total_written_bytes,
+            read_rate: Self::per_second(disk_usage.read_bytes, self.elapsed),
+            write_rate: Self::per_second(disk_usage.written_bytes, self.elapsed),
+            cgroup: self.cgroup_owners.get(pid, process.start_time()),
+            memory: None,
+        }
+    }
+}
+
+impl SysProcess for SysInfo {
+    fn get_processes(&self) -> Vec<ProcessRow> {
+        self.get_process_snapshot().rows()
+    }
+
+    fn get_processes_map(&self) -> HashMap<Pid, ProcessRow> {
+        self.system
+            .processes()
+            .iter()
+            // スレッドはプロセス一覧に含めない
+            .filter(|(_, process)| process.thread_kind().is_none())
+            .map(|(pid, process)| (*pid, self.to_process_row(*pid, process)))
+            .collect()
+    }
+
+    fn get_process_snapshot(&self) -> ProcessSnapshot {
+        ProcessSnapshot::new(SystemTime::now(), self.get_processes_map())
+    }
+
+    fn get_process_detail(&self, pid: Pid) -> AppResult<detail::ProcessDetail> {
+        detail::ProcessDetail::collect(Path::new(PROC_ROOT), pid)
+    }
+
+    fn get_process_memory(&self, pid: Pid) -> Option<memory::MemoryBreakdown> {
+        memory::read_breakdown(Path::new(PROC_ROOT), pid)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::time::SystemTime;
+
+    use shared::error::AppResult;
+
+    use crate::system::SysInfo;
+
+    use sysinfo::{Pid, ProcessStatus};
+
+    use super::{ProcessColumn, ProcessFilter, ProcessRow, ProcessSort, SortOrder, SysProcess};
+
+    pub(crate) fn row(pid: u32, name: &str, cpu_usage: f32, rss: u64) -> ProcessRow {
+        ProcessRow {
+            pid: Pid::from_u32(pid),
+            name: name.into(),
+            user: "root".into(),
+            status: ProcessStatus::Sleep,
+            cpu_usage,
+            rss,
+            virtual_memory: rss * 2,
+            cmd: format!("/usr/bin/{name}"),
+            ..Default::default()
+        }
+    }
+
+    fn pids(rows: &[ProcessRow]) -> Vec<u32> {
+        rows.iter().map(|row| row.pid.as_u32()).collect()
+    }
+
+    #[test]
+    fn test_process_sort() -> AppResult<()> {
+        let mut rows = vec![
+            row(3, "bash", 10., 300),
+            row(1, "init", 50., 100),
+            row(2, "Cargo", 10., 200),
+        ];
+
+        let mut sort = ProcessSort::default();
+        sort.apply(&mut rows);
+        assert_eq!(vec![1, 2, 3], pids(&rows));
+
+        sort.next_column();
+        assert_eq!(ProcessColumn::Ppid, sort.column);
+
+        sort.column = ProcessColumn::Cpu;
+        sort.order = SortOrder::Descending;
+        sort.apply(&mut rows);
+        // 同じCPU使用率はPID順
+        assert_eq!(vec![1, 2, 3], pids(&rows));
+
+        sort.toggle_order();
+        sort.apply(&mut rows);
+        assert_eq!(vec![2, 3, 1], pids(&rows));
+
+        sort.column = ProcessColumn::Name;
+        sort.order = SortOrder::Ascending;
+        sort.apply(&mut rows);
+        assert_eq!(vec![3, 2, 1], pids(&rows));
+        Ok(())
+    }
+
+    #[test]
+    fn test_process_filter() -> AppResult<()> {
+        let rows = [
+            row(1, "systemd", 0., 0),
+            row(42, "Cargo", 0., 0),
+            row(420, "bash", 0., 0),
+        ];
+        let matched = |filter: &ProcessFilter| -> Vec<u32> {
+            rows.iter()
+                .filter(|row| filter.matches(row))
+                .map(|row| row.pid.as_u32())
+                .collect()
+        };
+
+        let mut filter = ProcessFilter::default();
+        assert!(!filter.is_active());
+        assert_eq!(vec![1, 42, 420], matched(&filter));
+
+        "42".chars().for_each(|c| filter.push(c));
+        assert_eq!(vec![42, 420], matched(&filter));
+
+        filter.clear();
+        "cargo".chars().for_each(|c| filter.push(c));
+        assert_eq!(Vec::<u32>::new(), matched(&filter));
+        filter.toggle_ignore_case();
+        assert_eq!(vec![42], matched(&filter));
+
+        // コマンドラインにも一致する
+        filter.clear();
+        "/usr/bin/ba".chars().for_each(|c| filter.push(c));
+        assert_eq!(vec![420], matched(&filter));
+
+        filter.clear();
+        filter.toggle_regex();
+        "^(systemd|bash)$".chars().for_each(|c| filter.push(c));
+        assert_eq!(vec![1, 420], matched(&filter));
+
+        filter.pop();
+        assert_eq!("^(systemd|bash)", filter.get_query());
+        assert_eq!(vec![1, 420], matched(&filter));
+
+        filter.clear();
+        "(".chars().for_each(|c| filter.push(c));
+        assert!(filter.get_error().is_some());
+        assert_eq!(Vec::<u32>::new(), matched(&filter));
+        Ok(())
+    }
+
+    #[test]
+    fn test_process_column_next() -> AppResult<()> {
+        assert_eq!(ProcessColumn::Pid, ProcessColumn::Command.next());
+        assert_eq!(SortOrder::Descending, ProcessColumn::Cpu.default_order());
+        assert_eq!(SortOrder::Descending, ProcessColumn::WriteRate.default_order());
+        assert_eq!(SortOrder::Ascending, ProcessColumn::Name.default_order());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_by_disk_io() -> AppResult<()> {
+        let mut rows = vec![row(1, "a", 0., 0), row(2, "b", 0., 0), row(3, "c", 0., 0)];
+        rows[0].write_rate = 10;
+        rows[1].write_rate = 300;
+        rows[2].write_rate = 20;
+
+        let sort = ProcessSort {
+            column: ProcessColumn::WriteRate,
+            order: ProcessColumn::WriteRate.default_order(),
+        };
+        sort.apply(&mut rows);
+        assert_eq!(vec![2, 3, 1], pids(&rows));
+        assert_eq!("300B", ProcessColumn::WriteRate.cell(&rows[0]));
+
+        let mut total = rows[0].clone();
+        total.add_usage(&rows[1]);
+        assert_eq!(320, total.write_rate);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_processes() -> AppResult<()> {
+        let si = SysInfo::new();
+        let processes = si.get_processes();
+
+        assert_ne!(0, processes.len());
+        assert!(processes.windows(2).all(|w| w[0].pid < w[1].pid));
+        Ok(())
+    }
+
+    #[test]
+    fn test_process_row_self() -> AppResult<()> {
+        let si = SysInfo::new();
+        let pid = sysinfo::get_current_pid().unwrap();
+        let row = si
+            .get_processes()
+            .into_iter()
+            .find(|row| row.pid == pid)
+            .unwrap();
+
+        assert_ne!("", row.name);
+        assert_ne!("", row.cmd);
+        assert_ne!(0, row.rss);
+        assert_eq!(pid.to_string(), ProcessColumn::Pid.cell(&row));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_processes_map() -> AppResult<()> {
+        let si = SysInfo::new();
+        let pid = sysinfo::get_current_pid().unwrap();
+        let map = si.get_processes_map();
+
+        assert_ne!(0, map.len());
+        assert!(map.iter().all(|(key, row)| *key == row.pid));
+        assert!(map.contains_key(&pid));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_process_snapshot() -> AppResult<()> {
+        let mut si = SysInfo::new();
+        let pid = sysinfo::get_current_pid().unwrap();
+        let snapshot = si.get_process_snapshot();
+        si.refresh_all();
+
+        // 取得後にSysInfoを更新しても内容は変わらない
+        let row = snapshot.get(&pid).cloned().unwrap();
+        assert_eq!(pid, row.pid);
+        assert_eq!(snapshot.len(), snapshot.rows().len());
+        assert!(snapshot.get_taken_at() > SystemTime::UNIX_EPOCH);
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+use std::{fmt, io};
+
+use shared::error::{AppError, AppResult};
+use sysinfo::Pid;
+
+use super::SysInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessSignal {
+    Term,
+    Kill,
+    Stop,
+    Cont,
+    Hup,
+    /// 任意のシグナル番号
+    Other(i32),
+}
+
+impl ProcessSignal {
+    /// シグナル選択メニューに並べる順
+    pub const MENU: [ProcessSignal; 5] = [
+        ProcessSignal::Term,
+        ProcessSignal::Kill,
+        ProcessSignal::Stop,
+        ProcessSignal::Cont,
+        ProcessSignal::Hup,
+    ];
+    /// Linuxのリアルタイムシグナルの上限
+    const MAX_NUMBER: i32 = 64;
+
+    pub fn number(self) -> i32 {
+        match self {
+            Self::Term => libc::SIGTERM,
+            Self::Kill => libc::SIGKILL,
+            Self::Stop => libc::SIGSTOP,
+            Self::Cont => libc::SIGCONT,
+            Self::Hup => libc::SIGHUP,
+            Self::Other(number) => number,
+        }
+    }
+
+    /// `9`や`SIGKILL`、`kill`のような入力を解釈する
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if let Ok(number) = input.parse::<i32>() {
+            return (1..=Self::MAX_NUMBER)
+                .contains(&number)
+                .then(|| Self::from_number(number));
+        }
+
+        let name = input.to_uppercase();
+        let name = name.strip_prefix("SIG").unwrap_or(&name);
+        Self::MENU
+            .into_iter()
+            .find(|signal| signal.name().strip_prefix("SIG") == Some(name))
+    }
+
+    fn from_number(number: i32) -> Self {
+        Self::MENU
+            .into_iter()
+            .find(|signal| signal.number() == number)
+            .unwrap_or(Self::Other(number))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Term => "SIGTERM",
+            Self::Kill => "SIGKILL",
+            Self::Stop => "SIGSTOP",
+            Self::Cont => "SIGCONT",
+            Self::Hup => "SIGHUP",
+            Self::Other(_) => "SIG",
+        }
+    }
+}
+
+impl fmt::Display for ProcessSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Other(number) => write!(f, "signal {number}"),
+            _ => write!(f, "{} ({})", self.name(), self.number()),
+        }
+    }
+}
+
+pub trait SysSignal {
+    fn send_signal(&self, pid: Pid, signal: ProcessSignal) -> AppResult<()>;
+}
+
+/// sysinfoの`Process::kill_with`は成否しか返さず任意の番号も送れないので、
+/// EPERMやESRCHを画面に出せるよう直接kill(2)を呼ぶ
+impl SysSignal for SysInfo {
+    fn send_signal(&self, pid: Pid, signal: ProcessSignal) -> AppResult<()> {
+        let signal_error = |source| AppError::SignalError {
+            pid: pid.as_u32(),
+            signal: signal.to_string(),
+            source,
+        };
+
+        // 0や負のPIDはプロセスグループ宛てになるので送らない
+        let raw_pid = match libc::pid_t::try_from(pid.as_u32()) {
+            Ok(raw_pid) if raw_pid > 0 => raw_pid,
+            _ => return Err(signal_error(io::Error::from_raw_os_error(libc::ESRCH))),
+        };
+
+        let result = unsafe { libc::kill(raw_pid, signal.number()) };
+        if result == 0 {
+            Ok(())
+        } else {
+            Err(signal_error(io::Error::last_os_error()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::process::Command;
+
+    use shared::error::{AppError, AppResult};
+    use sysinfo::Pid;
+
+    use crate::system::SysInfo;
+
+    use super::{ProcessSignal, SysSignal};
+
+    #[test]
+    fn test_parse_signal() -> AppResult<()> {
+        assert_eq!(Some(ProcessSignal::Kill), ProcessSignal::parse("9"));
+        assert_eq!(Some(ProcessSignal::Kill), ProcessSignal::parse("sigkill"));
+        assert_eq!(Some(ProcessSignal::Hup), ProcessSignal::parse("HUP"));
+        assert_eq!(Some(ProcessSignal::Other(10)), ProcessSignal::parse("10"));
+        assert_eq!(None, ProcessSignal::parse("0"));
+        assert_eq!(None, ProcessSignal::parse("foo"));
+        assert_eq!("SIGTERM (15)", ProcessSignal::Term.to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_send_signal() -> AppResult<()> {
+        let si = SysInfo::new();
+        let mut child = Command::new("sleep").arg("30").spawn()?;
+        let pid = Pid::from_u32(child.id());
+
+        si.send_signal(pid, ProcessSignal::Kill)?;
+        let status = child.wait()?;
+        assert!(!status.success());
+        Ok(())
+    }
+
+    #[test]
+    fn test_send_signal_error() -> AppResult<()> {
+        let si = SysInfo::new();
+        let result = si.send_signal(Pid::from_u32(0), ProcessSignal::Term);
+        assert!(matches!(result, Err(AppError::SignalError { pid: 0, .. })));
+
+        let mut child = Command::new("true").spawn()?;
+        let pid = Pid::from_u32(child.id());
+        child.wait()?;
+        let result = si.send_signal(pid, ProcessSignal::Term);
+        match result {
+            Err(AppError::SignalError { source, .. }) => {
+                assert_eq!(Some(libc::ESRCH), source.raw_os_error())
+            }
+            _ => panic!("reaped process must not receive a signal"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::{HashMap, HashSet};
+
+use sysinfo::Pid;
+
+use super::{ProcessFilter, ProcessRow, ProcessSort};
+
+/// ツリー表示の1行。`prefix`は罫線と折りたたみ記号
+#[derive(Debug, Clone)]
+pub struct TreeRow {
+    pub row: ProcessRow,
+    pub prefix: String,
+}
+
+struct ProcessTree<'a> {
+    children: HashMap<Pid, Vec<&'a ProcessRow>>,
+    collapsed: &'a HashSet<Pid>,
+}
+
+impl<'a> ProcessTree<'a> {
+    const BRANCH: &'static str = "├─ ";
+    const LAST_BRANCH: &'static str = "└─ ";
+    const GUIDE: &'static str = "│  ";
+    const SPACE: &'static str = "   ";
+    const COLLAPSED: &'static str = "[+] ";
+
+    /// 折りたたんだ子孫の使用量を`row`に足す
+    fn add_subtree_usage(&self, row: &mut ProcessRow, pid: Pid) {
+        for child in self.children.get(&pid).into_iter().flatten() {
+            row.add_usage(child);
+            self.add_subtree_usage(row, child.pid);
+        }
+    }
+
+    fn walk(&self, row: &ProcessRow, guides: &str, branch: &str, out: &mut Vec<TreeRow>) {
+        let children = self.children.get(&row.pid);
+        let collapsed = children.is_some() && self.collapsed.contains(&row.pid);
+
+        let mut row = row.clone();
+        let mut prefix = format!("{guides}{branch}");
+        if collapsed {
+            let pid = row.pid;
+            self.add_subtree_usage(&mut row, pid);
+            prefix.push_str(Self::COLLAPSED);
+        }
+        out.push(TreeRow { row, prefix });
+
+        if collapsed {
+            return;
+        }
+        let guides = match branch {
+            Self::BRANCH => format!("{guides}{}", Self::GUIDE),
+            Self::LAST_BRANCH => format!("{guides}{}", Self::SPACE),
+            _ => guides.to_owned(),
+        };
+        let children = children.map(Vec::as_slice).unwrap_or_default();
+        for (i, child) in children.iter().enumerate() {
+            let branch = if i + 1 == children.len() {
+                Self::LAST_BRANCH
+            } else {
+                Self::BRANCH
+            };
+            self.walk(child, &guides, branch, out);
+        }
+    }
+}
+
+/// 親PIDで子プロセスをまとめる。兄弟は`sort`の順に並べる。
+/// 絞り込み中は一致したプロセスの祖先も残して、どこから起動されたかを見えるようにする
+pub fn build_tree(
+    rows: &[ProcessRow],
+    filter: &ProcessFilter,
+    sort: &ProcessSort,
+    collapsed: &HashSet<Pid>,
+) -> Vec<TreeRow> {
+    let by_pid = rows
+        .iter()
+        .map(|row| (row.pid, row))
+        .collect::<HashMap<_, _>>();
+
+    let keep = if filter.is_active() {
+        let mut keep = HashSet::new();
+        for row in rows.iter().filter(|row| filter.matches(row)) {
+            let mut pid = Some(row.pid);
+            while let Some(p) = pid {
+                if !keep.insert(p) {
+                    break;
+                }
+                pid = by_pid.get(&p).and_then(|row| row.ppid);
+            }
+        }
+        keep
+    } else {
+        by_pid.keys().copied().collect()
+    };
+
+    let mut roots = vec![];
+    let mut children: HashMap<Pid, Vec<&ProcessRow>> = HashMap::new();
+    for row in rows.iter().filter(|row| keep.contains(&row.pid)) {
+        match row
+            .ppid
+            .filter(|ppid| *ppid != row.pid && keep.contains(ppid))
+        {
+            Some(ppid) => children.entry(ppid).or_default().push(row),
+            None => roots.push(row),
+        }
+    }
+    roots.sort_by(|a, b| sort.compare(a, b));
+    children
+        .values_mut()
+        .for_each(|siblings| siblings.sort_by(|a, b| sort.compare(a, b)));
+
+    let tree = ProcessTree {
+        children,
+        collapsed,
+    };
+    let mut out = Vec::with_capacity(keep.len());
+    for root in roots {
+        tree.walk(root, "", "", &mut out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use shared::error::AppResult;
+    use sysinfo::Pid;
+
+    use crate::system::process::{
+        test::row, ProcessColumn, ProcessFilter, ProcessRow, ProcessSort, SortOrder,
+    };
+
+    use super::build_tree;
+
+    fn child(pid: u32, ppid: u32, name: &str, cpu_usage: f32) -> ProcessRow {
+        let mut row = row(pid, name, cpu_usage, 100);
+        row.ppid = Some(Pid::from_u32(ppid));
+        row
+    }
+
+    fn rows() -> Vec<ProcessRow> {
+        vec![
+            row(1, "init", 0., 100),
+            child(10, 1, "sshd", 1.),
+            child(11, 10, "bash", 2.),
+            child(12, 11, "make", 3.),
+            child(20, 1, "cron", 4.),
+        ]
+    }
+
+    fn lines(rows: &[super::TreeRow]) -> Vec<String> {
+        rows.iter()
+            .map(|r| format!("{}{}", r.prefix, r.row.name))
+            .collect()
+    }
+
+    #[test]
+    fn test_build_tree() -> AppResult<()> {
+        let tree = build_tree(
+            &rows(),
+            &ProcessFilter::default(),
+            &ProcessSort::default(),
+            &HashSet::new(),
+        );
+
+        assert_eq!(
+            vec![
+                "init",
+                "├─ sshd",
+                "│  └─ bash",
+                "│     └─ make",
+                "└─ cron",
+            ],
+            lines(&tree)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_tree_sorted_siblings() -> AppResult<()> {
+        let sort = ProcessSort {
+            column: ProcessColumn::Cpu,
+            order: SortOrder::Descending,
+        };
+        let tree = build_tree(&rows(), &ProcessFilter::default(), &sort, &HashSet::new());
+
+        assert_eq!("├─ cron", lines(&tree)[1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_tree_collapsed() -> AppResult<()> {
+        let collapsed = HashSet::from([Pid::from_u32(10)]);
+        let tree = build_tree(
+            &rows(),
+            &ProcessFilter::default(),
+            &ProcessSort::default(),
+            &collapsed,
+        );
+
+        assert_eq!(vec!["init", "├─ [+] sshd", "└─ cron"], lines(&tree));
+        let sshd = &tree[1].row;
+        assert_eq!(6., sshd.cpu_usage);
+        assert_eq!(300, sshd.rss);
+        Ok(())
+    }
+
+    #[test]
+    fn test_build_tree_filtered_keeps_ancestors() -> AppResult<()> {
+        let mut filter = ProcessFilter::default();
+        "make".chars().for_each(|c| filter.push(c));
+        let tree = build_tree(&rows(), &filter, &ProcessSort::default(), &HashSet::new());
+
+        assert_eq!(
+            vec!["init", "└─ sshd", "   └─ bash", "      └─ make"],
+            lines(&tree)
+        );
+        Ok(())
+    }
+}
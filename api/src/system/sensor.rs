@@ -0,0 +1,314 @@
+use std::{fs, path::Path};
+
+use strum::Display;
+
+use super::{SysInfo, SYS_ROOT};
+
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SensorKind {
+    #[strum(to_string = "TEMP")]
+    Temperature,
+    #[strum(to_string = "FAN")]
+    Fan,
+}
+
+/// 臨界値にどれだけ近いか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SensorLevel {
+    Normal,
+    Warm,
+    Hot,
+    Critical,
+}
+
+/// 温度は℃、ファンはRPM
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorReading {
+    /// hwmonの`name`。`coretemp`や`nvme`など
+    pub chip: String,
+    pub label: String,
+    pub kind: SensorKind,
+    pub value: f64,
+    /// 温度は上限、ファンは下限の警告値
+    pub limit: Option<f64>,
+    pub critical: Option<f64>,
+}
+
+impl SensorReading {
+    /// 臨界値(なければ上限)の20℃手前から段階的に色を変える
+    const WARM_MARGIN: f64 = 20.;
+    const HOT_MARGIN: f64 = 10.;
+
+    pub fn level(&self) -> SensorLevel {
+        match self.kind {
+            SensorKind::Temperature => {
+                let Some(threshold) = self.critical.or(self.limit) else {
+                    return SensorLevel::Normal;
+                };
+                match self.value {
+                    v if v >= threshold => SensorLevel::Critical,
+                    v if v >= threshold - Self::HOT_MARGIN => SensorLevel::Hot,
+                    v if v >= threshold - Self::WARM_MARGIN => SensorLevel::Warm,
+                    _ => SensorLevel::Normal,
+                }
+            }
+            // 下限を割ったファンは止まりかけている
+            SensorKind::Fan => match self.limit {
+                Some(min) if self.value < min => SensorLevel::Critical,
+                _ => SensorLevel::Normal,
+            },
+        }
+    }
+
+    /// `coretemp Core 0`のようにチップ名とラベルを並べたもの
+    pub fn name(&self) -> String {
+        format!("{} {}", self.chip, self.label)
+    }
+}
+
+/// `<sys_root>/class/hwmon/hwmon*`を読む。テストではfixtureのディレクトリを渡す
+pub fn read_hwmon(sys_root: &Path) -> Vec<SensorReading> {
+    let Ok(entries) = fs::read_dir(sys_root.join("class").join("hwmon")) else {
+        return vec![];
+    };
+    let mut dirs = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    dirs.sort_by_key(|dir| channel_number(dir));
+    dirs.iter().flat_map(|dir| read_chip(dir)).collect()
+}
+
+fn read_chip(dir: &Path) -> Vec<SensorReading> {
+    let chip = read_trimmed(&dir.join("name")).unwrap_or_else(|| "hwmon".into());
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut inputs = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let channel = name.strip_suffix("_input")?;
+            let kind = if channel.starts_with("temp") {
+                SensorKind::Temperature
+            } else if channel.starts_with("fan") {
+                SensorKind::Fan
+            } else {
+                return None;
+            };
+            Some((kind, channel.to_owned()))
+        })
+        .collect::<Vec<_>>();
+    inputs.sort_by_key(|(kind, channel)| (*kind, channel_number(Path::new(channel))));
+
+    inputs
+        .into_iter()
+        .filter_map(|(kind, channel)| {
+            // 温度はミリ℃で入っている
+            let scale = match kind {
+                SensorKind::Temperature => 1000.,
+                SensorKind::Fan => 1.,
+            };
+            let read = |suffix: &str| {
+                read_trimmed(&dir.join(format!("{channel}_{suffix}")))?
+                    .parse::<f64>()
+                    .ok()
+                    .map(|value| value / scale)
+            };
+            // センサーが眠っているNVMeなどは読むとエラーになる
+            let value = read("input")?;
+            let limit = match kind {
+                SensorKind::Temperature => read("max"),
+                SensorKind::Fan => read("min").filter(|min| *min > 0.),
+            };
+            let critical = read("crit");
+            Some(SensorReading {
+                chip: chip.clone(),
+                label: read_trimmed(&dir.join(format!("{channel}_label"))).unwrap_or(channel),
+                kind,
+                value,
+                limit,
+                critical,
+            })
+        })
+        .collect()
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|content| content.trim().to_owned())
+}
+
+/// `hwmon10`が`hwmon2`の後に来るよう番号で並べる
+fn channel_number(path: &Path) -> u32 {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.trim_start_matches(|c: char| c.is_ascii_alphabetic()))
+        .and_then(|number| number.parse().ok())
+        .unwrap_or(u32::MAX)
+}
+
+pub trait SysSensor {
+    fn refresh_sensors(&mut self);
+    /// Linuxではhwmon、それ以外ではsysinfoの`Components`の値
+    fn get_sensors(&self) -> Vec<SensorReading>;
+}
+
+impl SysSensor for SysInfo {
+    #[cfg(target_os = "linux")]
+    fn refresh_sensors(&mut self) {}
+
+    #[cfg(not(target_os = "linux"))]
+    fn refresh_sensors(&mut self) {
+        self.components.refresh();
+    }
+
+    /// sysinfoも同じhwmonを読むが、ラベルにデバイスのモデル名が付いて突き合わせられないので使わない
+    #[cfg(target_os = "linux")]
+    fn get_sensors(&self) -> Vec<SensorReading> {
+        read_hwmon(Path::new(SYS_ROOT))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn get_sensors(&self) -> Vec<SensorReading> {
+        self.components
+            .iter()
+            .filter(|component| component.temperature().is_finite())
+            .map(|component| SensorReading {
+                chip: component.label().into(),
+                label: String::new(),
+                kind: SensorKind::Temperature,
+                value: component.temperature() as f64,
+                limit: None,
+                critical: component.critical().map(f64::from),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::Path};
+
+    use shared::error::AppResult;
+
+    use crate::system::fixture::TempDir;
+
+    use super::{read_hwmon, SensorKind, SensorLevel, SensorReading};
+
+    fn fixture(root: &Path) -> AppResult<()> {
+        let hwmon = root.join("class").join("hwmon");
+        let files: [(&str, &[(&str, &str)]); 4] = [
+            // モデル名のあるNVMeも1つとして読む
+            (
+                "hwmon1",
+                &[
+                    ("name", "nvme\n"),
+                    ("device/model", "Samsung SSD 980 1TB\n"),
+                    ("temp1_input", "38850\n"),
+                    ("temp1_label", "Composite\n"),
+                    ("temp1_max", "84850\n"),
+                ],
+            ),
+            (
+                "hwmon2",
+                &[
+                    ("name", "coretemp\n"),
+                    ("temp1_input", "52000\n"),
+                    ("temp1_label", "Package id 0\n"),
+                    ("temp1_max", "80000\n"),
+                    ("temp1_crit", "100000\n"),
+                    ("temp10_input", "49000\n"),
+                    ("temp10_label", "Core 8\n"),
+                    ("temp2_input", "95000\n"),
+                    ("temp2_label", "Core 0\n"),
+                    ("temp2_crit", "100000\n"),
+                ],
+            ),
+            (
+                "hwmon10",
+                &[
+                    ("name", "nct6775\n"),
+                    ("fan1_input", "1200\n"),
+                    ("fan1_min", "300\n"),
+                    ("fan2_input", "0\n"),
+                    ("fan2_min", "300\n"),
+                    ("in0_input", "1000\n"),
+                ],
+            ),
+            // 読めないセンサーは飛ばす
+            ("hwmon0", &[("name", "nvme\n"), ("temp1_input", "")]),
+        ];
+        for (dir, entries) in files {
+            for (file, content) in entries {
+                let path = hwmon.join(dir).join(file);
+                fs::create_dir_all(path.parent().unwrap_or(&hwmon))?;
+                fs::write(path, content)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_hwmon() -> AppResult<()> {
+        let root = TempDir::new("hwmon")?;
+        fixture(&root)?;
+        let sensors = read_hwmon(&root);
+
+        assert_eq!(
+            vec![
+                "nvme Composite",
+                "coretemp Package id 0",
+                "coretemp Core 0",
+                "coretemp Core 8",
+                "nct6775 fan1",
+                "nct6775 fan2",
+            ],
+            sensors.iter().map(SensorReading::name).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            SensorReading {
+                chip: "coretemp".into(),
+                label: "Package id 0".into(),
+                kind: SensorKind::Temperature,
+                value: 52.,
+                limit: Some(80.),
+                critical: Some(100.),
+            },
+            sensors[1]
+        );
+        assert_eq!(Some(84.85), sensors[0].limit);
+        assert_eq!(1200., sensors[4].value);
+        assert!(read_hwmon(Path::new("/nonexistent")).is_empty());
+
+        assert_eq!(
+            vec![
+                SensorLevel::Normal,
+                SensorLevel::Normal,
+                SensorLevel::Hot,
+                SensorLevel::Normal,
+                SensorLevel::Normal,
+                SensorLevel::Critical,
+            ],
+            sensors.iter().map(SensorReading::level).collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_level() -> AppResult<()> {
+        let temp = |value, limit, critical| SensorReading {
+            chip: "k10temp".into(),
+            label: "Tctl".into(),
+            kind: SensorKind::Temperature,
+            value,
+            limit,
+            critical,
+        };
+        assert_eq!(SensorLevel::Warm, temp(70., Some(85.), None).level());
+        assert_eq!(SensorLevel::Critical, temp(90., Some(85.), Some(90.)).level());
+        assert_eq!(SensorLevel::Normal, temp(120., None, None).level());
+        Ok(())
+    }
+}
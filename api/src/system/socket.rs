@@ -0,0 +1,377 @@
+use std::{
+    collections::HashMap,
+    fs,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    path::Path,
+};
+
+use strum::{Display, EnumIter, IntoEnumIterator};
+use sysinfo::Pid;
+
+use super::{SysInfo, PROC_ROOT};
+
+#[derive(Debug, Display, EnumIter, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SocketProtocol {
+    #[strum(to_string = "tcp")]
+    Tcp,
+    #[strum(to_string = "tcp6")]
+    Tcp6,
+    #[strum(to_string = "udp")]
+    Udp,
+    #[strum(to_string = "udp6")]
+    Udp6,
+    #[strum(to_string = "unix")]
+    Unix,
+}
+
+impl SocketProtocol {
+    /// `/proc/net`以下のファイル名
+    fn file_name(self) -> String {
+        self.to_string()
+    }
+
+    pub fn is_inet(self) -> bool {
+        self != Self::Unix
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SocketOwner {
+    pub pid: Pid,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SocketEntry {
+    pub protocol: SocketProtocol,
+    /// `ss`と同じ表記の状態
+    pub state: &'static str,
+    pub local: String,
+    pub remote: String,
+    pub local_port: Option<u16>,
+    pub inode: u64,
+    pub owners: Vec<SocketOwner>,
+}
+
+impl SocketEntry {
+    pub fn is_listening(&self) -> bool {
+        matches!(self.state, "LISTEN" | "UNCONN")
+    }
+}
+
+/// `0100007F:0035`のような表記を読む。アドレスはカーネルの値がそのまま16進で出ている
+fn parse_address(field: &str) -> Option<(IpAddr, u16)> {
+    let (addr, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let addr = match addr.len() {
+        8 => IpAddr::V4(Ipv4Addr::from(
+            u32::from_str_radix(addr, 16).ok()?.to_ne_bytes(),
+        )),
+        32 => {
+            let mut octets = [0u8; 16];
+            for (i, chunk) in octets.chunks_mut(4).enumerate() {
+                let word = u32::from_str_radix(addr.get(i * 8..i * 8 + 8)?, 16).ok()?;
+                chunk.copy_from_slice(&word.to_ne_bytes());
+            }
+            IpAddr::V6(Ipv6Addr::from(octets))
+        }
+        _ => return None,
+    };
+    Some((addr, port))
+}
+
+fn format_address(addr: IpAddr, port: u16) -> String {
+    let port = match port {
+        0 => "*".to_owned(),
+        port => port.to_string(),
+    };
+    match addr {
+        IpAddr::V4(addr) => format!("{addr}:{port}"),
+        IpAddr::V6(addr) => format!("[{addr}]:{port}"),
+    }
+}
+
+fn inet_state(protocol: SocketProtocol, code: &str) -> &'static str {
+    match (protocol, code) {
+        (SocketProtocol::Udp | SocketProtocol::Udp6, "07") => "UNCONN",
+        (_, "01") => "ESTAB",
+        (_, "02") => "SYN-SENT",
+        (_, "03") => "SYN-RECV",
+        (_, "04") => "FIN-WAIT-1",
+        (_, "05") => "FIN-WAIT-2",
+        (_, "06") => "TIME-WAIT",
+        (_, "07") => "CLOSE",
+        (_, "08") => "CLOSE-WAIT",
+        (_, "09") => "LAST-ACK",
+        (_, "0A") => "LISTEN",
+        (_, "0B") => "CLOSING",
+        _ => "UNKNOWN",
+    }
+}
+
+/// `/proc/net/{tcp,tcp6,udp,udp6}`の中身を読む
+pub fn parse_inet(content: &str, protocol: SocketProtocol) -> Vec<SocketEntry> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            // sl local rem st queue tr retrnsmt uid timeout inode
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let (local, local_port) = parse_address(fields.get(1)?)?;
+            let (remote, remote_port) = parse_address(fields.get(2)?)?;
+            Some(SocketEntry {
+                protocol,
+                state: inet_state(protocol, fields.get(3)?),
+                local: format_address(local, local_port),
+                remote: format_address(remote, remote_port),
+                local_port: Some(local_port),
+                inode: fields.get(9)?.parse().ok()?,
+                owners: vec![],
+            })
+        })
+        .collect()
+}
+
+/// `/proc/net/unix`の中身を読む
+pub fn parse_unix(content: &str) -> Vec<SocketEntry> {
+    // 待ち受け中のソケットに立つ__SO_ACCEPTCON
+    const ACCEPTCON: u32 = 0x10000;
+
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            // Num RefCount Protocol Flags Type St Inode Path
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let flags = u32::from_str_radix(fields.get(3)?, 16).ok()?;
+            let state = match *fields.get(5)? {
+                _ if flags & ACCEPTCON != 0 => "LISTEN",
+                "03" => "ESTAB",
+                _ => "UNCONN",
+            };
+            Some(SocketEntry {
+                protocol: SocketProtocol::Unix,
+                state,
+                local: fields.get(7).unwrap_or(&"*").to_string(),
+                remote: "*".into(),
+                local_port: None,
+                inode: fields.get(6)?.parse().ok()?,
+                owners: vec![],
+            })
+        })
+        .collect()
+}
+
+/// `net_dir`は`/proc/net`か`/proc/<pid>/net`。読めないファイルは飛ばす
+pub fn read_sockets(net_dir: &Path) -> Vec<SocketEntry> {
+    SocketProtocol::iter()
+        .filter_map(|protocol| {
+            let content = fs::read_to_string(net_dir.join(protocol.file_name())).ok()?;
+            Some(match protocol {
+                SocketProtocol::Unix => parse_unix(&content),
+                _ => parse_inet(&content, protocol),
+            })
+        })
+        .flatten()
+        .collect()
+}
+
+/// `/proc/<pid>/fd`のうち`socket:[inode]`を指すもの
+pub fn socket_inodes(proc_root: &Path, pid: Pid) -> Vec<u64> {
+    let Ok(entries) = fs::read_dir(proc_root.join(pid.to_string()).join("fd")) else {
+        return vec![];
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let target = fs::read_link(entry.path()).ok()?;
+            target
+                .to_str()?
+                .strip_prefix("socket:[")?
+                .strip_suffix(']')?
+                .parse()
+                .ok()
+        })
+        .collect()
+}
+
+fn read_pids(proc_root: &Path) -> Vec<Pid> {
+    fs::read_dir(proc_root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .map(Pid::from_u32)
+        .collect()
+}
+
+/// 全プロセスのfdを見てソケットの持ち主を埋める。権限がなく見えないプロセスの分は空のまま
+pub fn collect_sockets(proc_root: &Path) -> Vec<SocketEntry> {
+    let mut owners: HashMap<u64, Vec<SocketOwner>> = HashMap::new();
+    for pid in read_pids(proc_root) {
+        let inodes = socket_inodes(proc_root, pid);
+        if inodes.is_empty() {
+            continue;
+        }
+        let name = fs::read_to_string(proc_root.join(pid.to_string()).join("comm"))
+            .map(|comm| comm.trim_end().to_owned())
+            .unwrap_or_default();
+        for inode in inodes {
+            let owner = SocketOwner {
+                pid,
+                name: name.clone(),
+            };
+            let entry = owners.entry(inode).or_default();
+            // 同じソケットを複数のfdで持っていても1回だけ数える
+            if !entry.contains(&owner) {
+                entry.push(owner);
+            }
+        }
+    }
+
+    let mut sockets = read_sockets(&proc_root.join("net"));
+    for socket in sockets.iter_mut() {
+        if let Some(owners) = owners.remove(&socket.inode) {
+            socket.owners = owners;
+        }
+    }
+    sockets.sort_by(|a, b| {
+        a.protocol
+            .cmp(&b.protocol)
+            .then(a.local_port.cmp(&b.local_port))
+            .then_with(|| a.local.cmp(&b.local))
+    });
+    sockets
+}
+
+/// プロセスが持っているソケット。プロセスのネットワーク名前空間の表から探す
+pub fn process_sockets(proc_root: &Path, pid: Pid) -> Option<Vec<SocketEntry>> {
+    let dir = proc_root.join(pid.to_string());
+    // fdを読めない場合は分からないので`None`
+    fs::read_dir(dir.join("fd")).ok()?;
+    let inodes = socket_inodes(proc_root, pid);
+    let mut sockets = read_sockets(&dir.join("net"));
+    sockets.retain(|socket| inodes.contains(&socket.inode));
+    sockets.sort_by(|a, b| a.protocol.cmp(&b.protocol).then(a.local_port.cmp(&b.local_port)));
+    Some(sockets)
+}
+
+pub trait SysSocket {
+    fn get_sockets(&self) -> Vec<SocketEntry>;
+}
+
+impl SysSocket for SysInfo {
+    fn get_sockets(&self) -> Vec<SocketEntry> {
+        collect_sockets(Path::new(PROC_ROOT))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, net::TcpListener, path::Path};
+
+    use shared::error::AppResult;
+
+    use crate::system::{fixture::TempDir, SysInfo};
+
+    use super::{
+        parse_inet, parse_unix, process_sockets, read_sockets, SocketProtocol, SysSocket,
+    };
+
+    const TCP: &str = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 23456 1 0000000000000000 100 0 0 10 0
+   1: 0F02000A:0016 0202000A:D431 01 00000000:00000000 02:0000A1B2 00000000     0        0 34567 4 0000000000000000 20 4 30 10 -1
+";
+
+    const TCP6: &str = "\
+  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000001000000:1F90 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 45678 1 0000000000000000 100 0 0 10 0
+";
+
+    const UDP: &str = "\
+   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  512: 00000000:0044 00000000:0000 07 00000000:00000000 00:00000000 00000000     0        0 56789 2 0000000000000000 0
+";
+
+    const UNIX: &str = "\
+Num       RefCount Protocol Flags    Type St Inode Path
+0000000000000000: 00000002 00000000 00010000 0001 01 11111 /run/systemd/private
+0000000000000000: 00000003 00000000 00000000 0001 03 22222
+0000000000000000: 00000002 00000000 00000000 0002 01 33333 @/tmp/.X11-unix/X0
+";
+
+    #[test]
+    fn test_parse_inet() -> AppResult<()> {
+        let tcp = parse_inet(TCP, SocketProtocol::Tcp);
+        assert_eq!(2, tcp.len());
+        assert_eq!("127.0.0.1:631", tcp[0].local);
+        assert_eq!("0.0.0.0:*", tcp[0].remote);
+        assert_eq!("LISTEN", tcp[0].state);
+        assert_eq!(23456, tcp[0].inode);
+        assert_eq!("10.0.2.15:22", tcp[1].local);
+        assert_eq!("10.0.2.2:54321", tcp[1].remote);
+        assert_eq!("ESTAB", tcp[1].state);
+        assert!(!tcp[1].is_listening());
+
+        let tcp6 = parse_inet(TCP6, SocketProtocol::Tcp6);
+        assert_eq!("[::1]:8080", tcp6[0].local);
+        assert_eq!(Some(8080), tcp6[0].local_port);
+
+        let udp = parse_inet(UDP, SocketProtocol::Udp);
+        assert_eq!("0.0.0.0:68", udp[0].local);
+        assert_eq!("UNCONN", udp[0].state);
+        assert!(udp[0].is_listening());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_unix() -> AppResult<()> {
+        let unix = parse_unix(UNIX);
+        let summary = unix
+            .iter()
+            .map(|s| (s.state, s.local.as_str(), s.inode))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("LISTEN", "/run/systemd/private", 11111),
+                ("ESTAB", "*", 22222),
+                ("UNCONN", "@/tmp/.X11-unix/X0", 33333),
+            ],
+            summary
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_sockets_fixture() -> AppResult<()> {
+        let dir = TempDir::new("net")?;
+        fs::write(dir.join("tcp"), TCP)?;
+        fs::write(dir.join("unix"), UNIX)?;
+
+        let sockets = read_sockets(&dir);
+
+        assert_eq!(5, sockets.len());
+        assert!(read_sockets(Path::new("/nonexistent")).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_own_listener() -> AppResult<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port();
+        let pid = sysinfo::get_current_pid().unwrap();
+
+        let own = process_sockets(Path::new("/proc"), pid).unwrap_or_default();
+        assert!(own
+            .iter()
+            .any(|s| s.local_port == Some(port) && s.state == "LISTEN"));
+
+        let all = SysInfo::new().get_sockets();
+        let socket = all
+            .iter()
+            .find(|s| s.protocol == SocketProtocol::Tcp && s.local_port == Some(port));
+        assert!(socket.is_some_and(|s| s.owners.iter().any(|o| o.pid == pid)));
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+use std::{fs, path::Path};
+
+use shared::error::AppResult;
+
+/// /proc/statのcpu行。単位はUSER_HZのtick
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CpuTimes {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+}
+
+impl CpuTimes {
+    /// guestとguest_niceはuserとniceに含まれているので足さない
+    fn parse(fields: &[&str]) -> Option<Self> {
+        let field = |idx: usize| fields.get(idx).and_then(|v| v.parse().ok());
+        Some(Self {
+            user: field(0)?,
+            nice: field(1)?,
+            system: field(2)?,
+            idle: field(3)?,
+            iowait: field(4).unwrap_or_default(),
+            irq: field(5).unwrap_or_default(),
+            softirq: field(6).unwrap_or_default(),
+            steal: field(7).unwrap_or_default(),
+        })
+    }
+
+    fn total(&self) -> u64 {
+        self.user
+            + self.nice
+            + self.system
+            + self.idle
+            + self.iowait
+            + self.irq
+            + self.softirq
+            + self.steal
+    }
+
+    /// CPUのオフラインなどで値が戻った場合は0にする
+    fn delta(&self, prev: &CpuTimes) -> CpuTimes {
+        CpuTimes {
+            user: self.user.saturating_sub(prev.user),
+            nice: self.nice.saturating_sub(prev.nice),
+            system: self.system.saturating_sub(prev.system),
+            idle: self.idle.saturating_sub(prev.idle),
+            iowait: self.iowait.saturating_sub(prev.iowait),
+            irq: self.irq.saturating_sub(prev.irq),
+            softirq: self.softirq.saturating_sub(prev.softirq),
+            steal: self.steal.saturating_sub(prev.steal),
+        }
+    }
+}
+
+/// 前回からの差分を割合(%)にしたもの
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct CpuBreakdown {
+    pub user: f64,
+    pub nice: f64,
+    pub system: f64,
+    pub idle: f64,
+    pub iowait: f64,
+    pub irq: f64,
+    pub softirq: f64,
+    pub steal: f64,
+}
+
+impl CpuBreakdown {
+    pub fn between(prev: &CpuTimes, next: &CpuTimes) -> Option<Self> {
+        let delta = next.delta(prev);
+        let total = delta.total();
+        if total == 0 {
+            return None;
+        }
+        let percent = |ticks: u64| ticks as f64 / total as f64 * 100.;
+        Some(Self {
+            user: percent(delta.user),
+            nice: percent(delta.nice),
+            system: percent(delta.system),
+            idle: percent(delta.idle),
+            iowait: percent(delta.iowait),
+            irq: percent(delta.irq),
+            softirq: percent(delta.softirq),
+            steal: percent(delta.steal),
+        })
+    }
+
+    /// 積み上げ表示の順。見落としやすいiowaitとstealを前に置き、idleは残りとする
+    pub fn segments(&self) -> [(&'static str, f64); 7] {
+        [
+            ("us", self.user),
+            ("sy", self.system),
+            ("wa", self.iowait),
+            ("st", self.steal),
+            ("ni", self.nice),
+            ("hi", self.irq),
+            ("si", self.softirq),
+        ]
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProcStat {
+    pub total: CpuTimes,
+    /// `cpuN`の順。オフラインのコアは行がないので番号は詰まることがある
+    pub cores: Vec<CpuTimes>,
+    /// 起動からの累計
+    pub context_switches: u64,
+    pub interrupts: u64,
+    /// 実行可能なタスクとI/O待ちで止まっているタスクの数
+    pub procs_running: u64,
+    pub procs_blocked: u64,
+}
+
+impl ProcStat {
+    pub fn read(proc_root: &Path) -> AppResult<Self> {
+        Ok(parse_stat(&fs::read_to_string(proc_root.join("stat"))?))
+    }
+
+    pub fn breakdown(&self, prev: &ProcStat) -> Option<CpuBreakdown> {
+        CpuBreakdown::between(&prev.total, &self.total)
+    }
+
+    /// コア数が変わった場合は比べられないので空にする
+    pub fn core_breakdowns(&self, prev: &ProcStat) -> Vec<CpuBreakdown> {
+        if self.cores.len() != prev.cores.len() {
+            return vec![];
+        }
+        prev.cores
+            .iter()
+            .zip(&self.cores)
+            .map(|(prev, next)| CpuBreakdown::between(prev, next).unwrap_or_default())
+            .collect()
+    }
+}
+
+pub fn parse_stat(content: &str) -> ProcStat {
+    let mut stat = ProcStat::default();
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let Some(key) = fields.next() else {
+            continue;
+        };
+        let values = fields.collect::<Vec<_>>();
+        // `intr`は合計の後に割り込み番号ごとの値が続く
+        let first = || values.first().and_then(|v| v.parse().ok()).unwrap_or_default();
+        match key {
+            "cpu" => stat.total = CpuTimes::parse(&values).unwrap_or_default(),
+            key if key.starts_with("cpu") => stat.cores.extend(CpuTimes::parse(&values)),
+            "ctxt" => stat.context_switches = first(),
+            "intr" => stat.interrupts = first(),
+            "procs_running" => stat.procs_running = first(),
+            "procs_blocked" => stat.procs_blocked = first(),
+            _ => {}
+        }
+    }
+    stat
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use shared::error::AppResult;
+
+    use super::{parse_stat, CpuBreakdown, CpuTimes, ProcStat};
+
+    const STAT: &str = "\
+cpu  1000 100 500 8000 300 20 30 50 0 0
+cpu0 600 50 250 3900 150 10 20 20 0 0
+cpu1 400 50 250 4100 150 10 10 30 0 0
+intr 123456 10 20 30
+ctxt 987654
+btime 1700000000
+processes 4242
+procs_running 3
+procs_blocked 1
+";
+
+    const STAT_NEXT: &str = "\
+cpu  1400 100 600 8200 500 20 30 150 0 0
+cpu0 800 50 300 4000 250 10 20 70 0 0
+cpu1 600 50 300 4200 250 10 10 80 0 0
+";
+
+    #[test]
+    fn test_parse_stat() -> AppResult<()> {
+        let stat = parse_stat(STAT);
+        assert_eq!(2, stat.cores.len());
+        assert_eq!(
+            CpuTimes {
+                user: 1000,
+                nice: 100,
+                system: 500,
+                idle: 8000,
+                iowait: 300,
+                irq: 20,
+                softirq: 30,
+                steal: 50,
+            },
+            stat.total
+        );
+        assert_eq!(600, stat.cores[0].user);
+        assert_eq!(987654, stat.context_switches);
+        assert_eq!(123456, stat.interrupts);
+        assert_eq!((3, 1), (stat.procs_running, stat.procs_blocked));
+        Ok(())
+    }
+
+    #[test]
+    fn test_breakdown() -> AppResult<()> {
+        let prev = parse_stat(STAT);
+        let next = parse_stat(STAT_NEXT);
+
+        // 差分は user 400, system 100, idle 200, iowait 200, steal 100 の計1000
+        let breakdown = next.breakdown(&prev);
+        assert_eq!(
+            Some(CpuBreakdown {
+                user: 40.,
+                system: 10.,
+                idle: 20.,
+                iowait: 20.,
+                steal: 10.,
+                ..Default::default()
+            }),
+            breakdown
+        );
+
+        let cores = next.core_breakdowns(&prev);
+        assert_eq!(2, cores.len());
+        assert_eq!(40., cores[0].user);
+        assert_eq!(10., cores[1].steal);
+
+        assert_eq!(None, prev.breakdown(&prev));
+        assert!(next.core_breakdowns(&ProcStat::default()).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_stat() -> AppResult<()> {
+        let stat = ProcStat::read(Path::new("/proc"))?;
+        assert!(!stat.cores.is_empty());
+        assert!(stat.total.user > 0);
+        assert!(ProcStat::read(Path::new("/nonexistent")).is_err());
+        Ok(())
+    }
+}
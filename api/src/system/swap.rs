@@ -1,35 +1,292 @@
-use super::SysInfo;
+use std::{fs, path::Path, sync::OnceLock};
+
+use shared::error::AppResult;
+
+use super::{memory::MemInfo, SysInfo, PROC_ROOT, SYS_ROOT};
+
+/// /proc/swapsの1行。サイズはバイト数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapDevice {
+    pub name: String,
+    /// `partition`か`file`
+    pub kind: String,
+    pub size: u64,
+    pub used: u64,
+    pub priority: i32,
+}
+
+/// zramデバイスの圧縮状況。`mm_stat`の先頭3つ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZramDevice {
+    pub name: String,
+    pub algorithm: Option<String>,
+    pub disk_size: u64,
+    /// 圧縮前、圧縮後、管理領域込みで実際に使っているメモリ
+    pub orig_data_size: u64,
+    pub compr_data_size: u64,
+    pub mem_used_total: u64,
+}
+
+impl ZramDevice {
+    pub fn ratio(&self) -> Option<f64> {
+        (self.compr_data_size > 0).then(|| self.orig_data_size as f64 / self.compr_data_size as f64)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZswapStats {
+    pub enabled: bool,
+    pub compressor: Option<String>,
+    /// 圧縮後のプールの大きさと、そこに入っている圧縮前のページの合計
+    pub pool_size: u64,
+    pub stored: u64,
+}
+
+impl ZswapStats {
+    pub fn ratio(&self) -> Option<f64> {
+        (self.pool_size > 0).then(|| self.stored as f64 / self.pool_size as f64)
+    }
+}
+
+/// スワップの詳細画面に出す値
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SwapSummary {
+    pub devices: Vec<SwapDevice>,
+    pub zram: Vec<ZramDevice>,
+    pub zswap: Option<ZswapStats>,
+    /// 毎秒のスワップイン・アウトのバイト数
+    pub swap_in: u64,
+    pub swap_out: u64,
+}
+
+/// vmstatのページ数をバイト数にするのに使う。起動中に変わらないので一度だけ聞く
+fn page_size() -> u64 {
+    static PAGE_SIZE: OnceLock<u64> = OnceLock::new();
+    *PAGE_SIZE.get_or_init(|| {
+        // SAFETY: sysconfは引数を読むだけでメモリに触らず、どのスレッドから呼んでもよい
+        let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        // 取れなければ多くの環境の値にしておく
+        u64::try_from(size).ok().filter(|size| *size > 0).unwrap_or(4096)
+    })
+}
+
+/// `/proc/swaps`を読む。空白は`\040`にエスケープされている
+pub fn parse_swaps(content: &str) -> Vec<SwapDevice> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some(SwapDevice {
+                name: fields.next()?.replace("\\040", " "),
+                kind: fields.next()?.to_owned(),
+                size: fields.next()?.parse::<u64>().ok()? * 1024,
+                used: fields.next()?.parse::<u64>().ok()? * 1024,
+                priority: fields.next()?.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+/// `<sys_root>/block/zram*`を読む。使われていないデバイスも含む
+pub fn read_zram(sys_root: &Path) -> Vec<ZramDevice> {
+    let Ok(entries) = fs::read_dir(sys_root.join("block")) else {
+        return vec![];
+    };
+    let mut devices = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            if !name.starts_with("zram") {
+                return None;
+            }
+            let dir = entry.path();
+            let mm_stat = fs::read_to_string(dir.join("mm_stat")).ok()?;
+            let mut stats = mm_stat
+                .split_whitespace()
+                .map(|value| value.parse::<u64>().ok());
+            Some(ZramDevice {
+                algorithm: fs::read_to_string(dir.join("comp_algorithm"))
+                    .ok()
+                    .and_then(|content| selected_algorithm(&content)),
+                disk_size: fs::read_to_string(dir.join("disksize"))
+                    .ok()
+                    .and_then(|size| size.trim().parse().ok())
+                    .unwrap_or_default(),
+                orig_data_size: stats.next()??,
+                compr_data_size: stats.next()??,
+                mem_used_total: stats.next()??,
+                name,
+            })
+        })
+        .collect::<Vec<_>>();
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+/// `lzo [lz4] zstd`のうち括弧の付いたものが使われている
+fn selected_algorithm(content: &str) -> Option<String> {
+    content
+        .split_whitespace()
+        .find_map(|algorithm| algorithm.strip_prefix('[')?.strip_suffix(']'))
+        .map(str::to_owned)
+}
+
+/// zswapのモジュールがなければ`None`。プールの大きさは/proc/meminfoから取る
+pub fn read_zswap(sys_root: &Path, meminfo: &MemInfo) -> Option<ZswapStats> {
+    let parameters = sys_root.join("module").join("zswap").join("parameters");
+    let read = |name: &str| {
+        fs::read_to_string(parameters.join(name))
+            .ok()
+            .map(|value| value.trim().to_owned())
+    };
+    Some(ZswapStats {
+        enabled: read("enabled")? == "Y",
+        compressor: read("compressor"),
+        pool_size: meminfo.zswap,
+        stored: meminfo.zswapped,
+    })
+}
 
 pub trait Swap {
+    /// 使用率(%)。スワップがなければ0
     fn get_swap(&self,)->f64;
+    /// バイト数
     fn get_total_swap(&self,)->u64;
+    fn get_swap_devices(&self)->AppResult<Vec<SwapDevice>>;
+    /// 毎秒スワップイン・アウトしたバイト数
+    fn get_swap_rates(&self)->(u64,u64);
+    fn get_swap_summary(&self)->SwapSummary;
 }
 
 impl Swap for SysInfo {
     fn get_swap(&self) -> f64 {
-        self.system.used_swap() as f64 / self.system.total_swap() as f64 * 100.
+        match self.system.total_swap() {
+            0 => 0.,
+            total => self.system.used_swap() as f64 / total as f64 * 100.,
+        }
     }
 
     fn get_total_swap(&self) -> u64 {
-        self.system.total_swap() / Self::GIB
+        self.system.total_swap()
+    }
+
+    fn get_swap_devices(&self) -> AppResult<Vec<SwapDevice>> {
+        Ok(parse_swaps(&fs::read_to_string(Path::new(PROC_ROOT).join("swaps"))?))
+    }
+
+    fn get_swap_rates(&self) -> (u64, u64) {
+        // vmstatの値はページ数
+        let page_size = page_size();
+        (
+            self.vmstat_rate(|vmstat| vmstat.pswpin) * page_size,
+            self.vmstat_rate(|vmstat| vmstat.pswpout) * page_size,
+        )
+    }
+
+    fn get_swap_summary(&self) -> SwapSummary {
+        let sys_root = Path::new(SYS_ROOT);
+        let (swap_in, swap_out) = self.get_swap_rates();
+        SwapSummary {
+            devices: self.get_swap_devices().unwrap_or_default(),
+            zram: read_zram(sys_root),
+            zswap: MemInfo::read(Path::new(PROC_ROOT))
+                .ok()
+                .and_then(|meminfo| read_zswap(sys_root, &meminfo)),
+            swap_in,
+            swap_out,
+        }
     }
 }
 
 
 #[cfg(test)]
 mod test {
+    use std::{fs, path::Path};
+
     use shared::error::AppResult;
 
-    use crate::system::{swap::Swap, SysInfo};
+    use crate::system::{fixture::TempDir, memory::MemInfo, swap::Swap, SysInfo};
 
+    use super::{parse_swaps, read_zram, read_zswap, SwapDevice};
+
+    const SWAPS: &str = "\
+Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority
+/dev/zram0                              partition\t8388604\t\t1024\t\t100
+/var/swap\\040file                       file\t\t2097148\t\t0\t\t-2
+";
 
     #[test]
     fn swap() -> AppResult<()> {
         let si = SysInfo::new();
         let swap = si.get_swap();
-        println!("{swap}");
-        let total_swap = si.get_total_swap();
-        assert_ne!(0,total_swap);
+        // スワップのない環境でもNaNにならない
+        assert!((0. ..=100.).contains(&swap));
+        if si.get_total_swap() == 0 {
+            assert_eq!(0., swap);
+        }
+        assert_eq!(
+            si.get_total_swap() / 1024,
+            si.get_swap_devices()?.iter().map(|device| device.size / 1024).sum::<u64>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_swaps() -> AppResult<()> {
+        let devices = parse_swaps(SWAPS);
+        assert_eq!(
+            vec![
+                SwapDevice {
+                    name: "/dev/zram0".into(),
+                    kind: "partition".into(),
+                    size: 8388604 * 1024,
+                    used: 1024 * 1024,
+                    priority: 100,
+                },
+                SwapDevice {
+                    name: "/var/swap file".into(),
+                    kind: "file".into(),
+                    size: 2097148 * 1024,
+                    used: 0,
+                    priority: -2,
+                },
+            ],
+            devices
+        );
+        assert!(parse_swaps("Filename\tType\tSize\tUsed\tPriority\n").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_zram_zswap() -> AppResult<()> {
+        let root = TempDir::new("zram")?;
+        let zram = root.join("block").join("zram0");
+        let zswap = root.join("module").join("zswap").join("parameters");
+        fs::create_dir_all(&zram)?;
+        fs::create_dir_all(root.join("block").join("vda"))?;
+        fs::create_dir_all(&zswap)?;
+        fs::write(zram.join("mm_stat"), "  4194304  1048576  1310720  0  1310720  12  0  0  0\n")?;
+        fs::write(zram.join("comp_algorithm"), "lzo lzo-rle [lz4] zstd\n")?;
+        fs::write(zram.join("disksize"), "8589934592\n")?;
+        fs::write(zswap.join("enabled"), "Y\n")?;
+        fs::write(zswap.join("compressor"), "zstd\n")?;
+
+        let devices = read_zram(&root);
+        let meminfo = MemInfo {
+            zswap: 1024,
+            zswapped: 3072,
+            ..Default::default()
+        };
+        let stats = read_zswap(&root, &meminfo);
+
+        assert_eq!(1, devices.len());
+        assert_eq!(Some("lz4"), devices[0].algorithm.as_deref());
+        assert_eq!(8589934592, devices[0].disk_size);
+        assert_eq!(Some(4.), devices[0].ratio());
+        assert_eq!(Some(true), stats.as_ref().map(|stats| stats.enabled));
+        assert_eq!(Some(Some(3.)), stats.map(|stats| stats.ratio()));
+        assert_eq!(None, read_zswap(Path::new("/nonexistent"), &meminfo));
         Ok(())
     }
 }
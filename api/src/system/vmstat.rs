@@ -0,0 +1,72 @@
+use std::{fs, path::Path};
+
+use shared::error::AppResult;
+
+use super::SysInfo;
+
+/// /proc/vmstatのうち使う値。どれも起動からの累計
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VmStat {
+    /// スワップから読み込んだ、スワップへ書き出したページ数
+    pub pswpin: u64,
+    pub pswpout: u64,
+    /// OOM killerがプロセスを殺した回数
+    pub oom_kill: u64,
+}
+
+impl VmStat {
+    pub fn read(proc_root: &Path) -> AppResult<Self> {
+        Ok(parse_vmstat(&fs::read_to_string(proc_root.join("vmstat"))?))
+    }
+}
+
+pub fn parse_vmstat(content: &str) -> VmStat {
+    let mut vmstat = VmStat::default();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(' ') else {
+            continue;
+        };
+        let value = value.trim().parse().unwrap_or_default();
+        match key {
+            "pswpin" => vmstat.pswpin = value,
+            "pswpout" => vmstat.pswpout = value,
+            "oom_kill" => vmstat.oom_kill = value,
+            _ => {}
+        }
+    }
+    vmstat
+}
+
+impl SysInfo {
+    /// /proc/vmstatの累計値を前回との差分から毎秒の値にする。初回の更新前は0
+    pub(super) fn vmstat_rate(&self, counter: impl Fn(&VmStat) -> u64) -> u64 {
+        match (&self.vmstat, &self.prev_vmstat) {
+            (Some(vmstat), Some(prev)) => {
+                Self::per_second(counter(vmstat).saturating_sub(counter(prev)), self.elapsed)
+            }
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use shared::error::AppResult;
+
+    use super::{parse_vmstat, VmStat};
+
+    #[test]
+    fn test_parse_vmstat() -> AppResult<()> {
+        let vmstat = parse_vmstat("nr_free_pages 12345\npswpin 42\npswpout 1024\noom_kill 3\n");
+        assert_eq!(
+            VmStat {
+                pswpin: 42,
+                pswpout: 1024,
+                oom_kill: 3,
+            },
+            vmstat
+        );
+        assert_eq!(VmStat::default(), parse_vmstat(""));
+        Ok(())
+    }
+}
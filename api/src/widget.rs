@@ -1,13 +1,42 @@
+use std::collections::{HashMap, HashSet};
+
 use ratatui::{
     buffer::Buffer,
-    layout::Rect,
-    style::{palette::tailwind, Stylize},
-    text::{Line, Text},
-    widgets::{Paragraph, StatefulWidget, StatefulWidgetRef, Widget, Wrap},
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{palette::tailwind, Color, Modifier, Style, Stylize},
+    symbols::border,
+    text::{Line, Span, Text},
+    widgets::{
+        Block, Cell, Clear, List, ListState, Paragraph, Row, StatefulWidget, StatefulWidgetRef,
+        Table, TableState, Widget, Wrap,
+    },
 };
-use strum::{Display, EnumIter, FromRepr};
+use shared::util::{DisplayBytes, DisplayDuration};
+use strum::{Display, EnumIter, FromRepr, IntoEnumIterator};
+use sysinfo::Pid;
 use tui_scrollview::{ScrollView, ScrollViewState};
 
+use crate::system::{
+    disk::DiskInfo,
+    diskstats::DiskIo,
+    frequency::CoreFrequency,
+    memory::MemInfo,
+    pressure::{PressureAvg, PressureResource},
+    sensor::{SensorKind, SensorLevel, SensorReading},
+    socket::SocketEntry,
+    stat::CpuBreakdown,
+    swap::SwapSummary,
+};
+use crate::system::process::{
+    aggregate::{aggregate, GroupBy, ProcessGroup},
+    detail::ProcessDetail,
+    event::{EventKind, EventLog, ProcessEvent},
+    memory::MemoryBreakdown,
+    signal::ProcessSignal,
+    tree::build_tree,
+    ProcessColumn, ProcessFilter, ProcessRow, ProcessSort,
+};
+
 #[derive(Default, Display, FromRepr, EnumIter, Clone, Copy, PartialEq, Eq)]
 pub enum SelectedTab {
     #[default]
@@ -15,6 +44,14 @@ pub enum SelectedTab {
     Main,
     #[strum(to_string = "Process")]
     Process,
+    #[strum(to_string = "Events")]
+    Events,
+    #[strum(to_string = "Ports")]
+    Ports,
+    #[strum(to_string = "Sensors")]
+    Sensors,
+    #[strum(to_string = "Disk I/O")]
+    DiskIo,
 }
 
 impl SelectedTab {
@@ -37,33 +74,2041 @@ impl SelectedTab {
     }
 }
 
+/// Mainタブのcpu表示
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CpuView {
+    #[default]
+    Global,
+    PerCore,
+    /// コアごとの周波数とgovernor
+    Frequency,
+}
+
+impl CpuView {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Global => Self::PerCore,
+            Self::PerCore => Self::Frequency,
+            Self::Frequency => Self::Global,
+        }
+    }
+}
+
+/// 1行目にgovernorと熱による制限の回数をまとめ、その下にコアごとの周波数を縦に並べる
+pub struct CpuFrequencyGrid<'a> {
+    cores: &'a [CoreFrequency],
+}
+
+impl<'a> CpuFrequencyGrid<'a> {
+    const CELL_SPACING: usize = 2;
+
+    pub fn new(cores: &'a [CoreFrequency]) -> Self {
+        Self { cores }
+    }
+
+    fn summary(&self) -> Line<'static> {
+        let mut governors = self
+            .cores
+            .iter()
+            .filter_map(|core| core.governor.clone())
+            .collect::<Vec<_>>();
+        governors.sort();
+        governors.dedup();
+        // 省電力のgovernorは性能低下の原因になりやすい
+        let governor = if governors.is_empty() {
+            "-".to_owned().dark_gray()
+        } else if governors.iter().any(|g| g == "powersave") {
+            governors.join("/").fg(tailwind::YELLOW.c400)
+        } else {
+            governors.join("/").fg(tailwind::GREEN.c500)
+        };
+
+        let core_throttles = self
+            .cores
+            .iter()
+            .filter_map(|core| core.core_throttles)
+            .reduce(|sum, count| sum + count);
+        // パッケージの値は同じパッケージのコアで重複している
+        let package_throttles = self.cores.iter().filter_map(|core| core.package_throttles).max();
+        let throttle = match (core_throttles, package_throttles) {
+            (None, None) => "-".to_owned().dark_gray(),
+            (core, package) => {
+                let count = |count: Option<u64>| count.map_or("-".into(), |c| c.to_string());
+                let text = format!("core {} / package {}", count(core), count(package));
+                if core.unwrap_or_default() + package.unwrap_or_default() > 0 {
+                    text.fg(tailwind::RED.c500)
+                } else {
+                    text.fg(tailwind::GREEN.c500)
+                }
+            }
+        };
+        Line::from(vec![
+            "Governor ".bold(),
+            governor,
+            "  Throttled ".bold(),
+            throttle,
+        ])
+    }
+
+    fn cell(core: &CoreFrequency, label_width: usize) -> String {
+        let mhz = |value: Option<u64>| value.map_or("-".into(), |v| v.to_string());
+        let range = match (core.min, core.max) {
+            (None, None) => String::new(),
+            (min, max) => format!(" {}-{}", mhz(min), mhz(max)),
+        };
+        format!("{:>label_width$} {:>4}MHz{range}", core.core, core.current)
+    }
+}
+
+impl Widget for CpuFrequencyGrid<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        self.summary().render(Rect::new(area.x, area.y, area.width, 1), buf);
+        if self.cores.is_empty() || area.height < 2 {
+            return;
+        }
+
+        let label_width = self
+            .cores
+            .iter()
+            .map(|core| core.core.to_string().len())
+            .max()
+            .unwrap_or_default();
+        let cells = self
+            .cores
+            .iter()
+            .map(|core| Self::cell(core, label_width))
+            .collect::<Vec<_>>();
+        let cell_width = cells.iter().map(|cell| cell.len()).max().unwrap_or_default()
+            + Self::CELL_SPACING;
+        let rows = (area.height - 1) as usize;
+        let columns = (area.width as usize / cell_width).max(1);
+        // 収まらないコアは描かない
+        for (idx, cell) in cells.iter().take(rows * columns).enumerate() {
+            buf.set_stringn(
+                area.x + ((idx / rows) * cell_width) as u16,
+                area.y + 1 + (idx % rows) as u16,
+                cell,
+                area.width as usize - (idx / rows) * cell_width,
+                Style::new(),
+            );
+        }
+    }
+}
+
+/// コアごとの使用率を棒グラフで縦に並べ、下まで埋まったら次の列に移る。
+/// 棒グラフが収まらないほどコアが多い時は1文字ずつの濃淡表示を横に並べる
+pub struct CpuGrid<'a> {
+    usages: &'a [f32],
+    /// あれば棒を内訳ごとに色分けする
+    breakdowns: &'a [CpuBreakdown],
+}
+
+impl<'a> CpuGrid<'a> {
+    const LEVELS: [&'static str; 8] = ["▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+    /// 番号、棒、` 100%`が読める最小の幅
+    const MIN_BAR_CELL: u16 = 12;
+
+    pub fn new(usages: &'a [f32]) -> Self {
+        Self {
+            usages,
+            breakdowns: &[],
+        }
+    }
+
+    pub fn breakdowns(mut self, breakdowns: &'a [CpuBreakdown]) -> Self {
+        self.breakdowns = breakdowns;
+        self
+    }
+
+    fn color(usage: f32) -> Color {
+        match usage {
+            u if u >= 80. => tailwind::RED.c500,
+            u if u >= 50. => tailwind::YELLOW.c500,
+            _ => tailwind::GREEN.c500,
+        }
+    }
+
+    fn bar_cell(
+        idx: usize,
+        usage: f32,
+        breakdown: Option<&CpuBreakdown>,
+        label_width: usize,
+        width: u16,
+    ) -> Line<'static> {
+        let usage = usage.clamp(0., 100.);
+        // 番号と空白、` 100%`、列の間の空白を除いた分が棒
+        let bar_width = (width as usize).saturating_sub(label_width + 1 + 5 + 1);
+        let mut spans = vec![format!("{idx:>label_width$} ").into()];
+        match breakdown {
+            Some(breakdown) => spans.extend(CpuBreakdownBar::spans(breakdown, bar_width)),
+            None => {
+                let filled = ((usage / 100.) * bar_width as f32).round() as usize;
+                spans.push("█".repeat(filled).fg(Self::color(usage)));
+                spans.push("░".repeat(bar_width - filled).fg(tailwind::SLATE.c700));
+            }
+        }
+        spans.push(format!(" {usage:>3.0}%").into());
+        Line::from(spans)
+    }
+
+    fn level(usage: f32) -> &'static str {
+        let level = (usage.clamp(0., 100.) / 100. * (Self::LEVELS.len() - 1) as f32).round();
+        Self::LEVELS[level as usize]
+    }
+}
+
+impl Widget for CpuGrid<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if self.usages.is_empty() || area.is_empty() {
+            return;
+        }
+        let rows = self.usages.len().min(area.height as usize);
+        let columns = self.usages.len().div_ceil(rows);
+        let cell_width = area.width / columns as u16;
+
+        if cell_width >= Self::MIN_BAR_CELL {
+            let label_width = (self.usages.len() - 1).to_string().len();
+            for (idx, usage) in self.usages.iter().enumerate() {
+                let cell = Rect::new(
+                    area.x + (idx / rows) as u16 * cell_width,
+                    area.y + (idx % rows) as u16,
+                    cell_width,
+                    1,
+                );
+                let breakdown = self.breakdowns.get(idx);
+                Self::bar_cell(idx, *usage, breakdown, label_width, cell_width).render(cell, buf);
+            }
+        } else {
+            // 1文字でも収まらない分は描かない
+            let columns = (area.width as usize).min(columns);
+            let rows = self.usages.len().div_ceil(columns).min(area.height as usize);
+            for (idx, usage) in self.usages.iter().take(rows * columns).enumerate() {
+                buf.set_string(
+                    area.x + (idx % columns) as u16,
+                    area.y + (idx / columns) as u16,
+                    Self::level(*usage),
+                    Style::new().fg(Self::color(*usage)),
+                );
+            }
+        }
+    }
+}
+
+/// 割合(0〜1)ごとに色を変えた積み上げの棒。
+/// 累積値で区切りを丸めるので、合計の幅は常に`width`になる
+fn stacked_spans(segments: impl IntoIterator<Item = (f64, Color)>, width: usize) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut total = 0.;
+    let mut drawn = 0;
+    for (ratio, color) in segments {
+        total += ratio;
+        let end = (total.clamp(0., 1.) * width as f64).round() as usize;
+        if end > drawn {
+            spans.push("█".repeat(end - drawn).fg(color));
+            drawn = end;
+        }
+    }
+    spans.push("░".repeat(width - drawn).fg(tailwind::SLATE.c700));
+    spans
+}
+
+/// CPU時間の内訳を積み上げた棒と凡例。高さが2行あれば凡例も出す
+pub struct CpuBreakdownBar<'a> {
+    breakdown: &'a CpuBreakdown,
+}
+
+impl<'a> CpuBreakdownBar<'a> {
+    /// `CpuBreakdown::segments`と同じ順
+    const COLORS: [Color; 7] = [
+        tailwind::GREEN.c500,
+        tailwind::RED.c500,
+        tailwind::YELLOW.c400,
+        tailwind::CYAN.c400,
+        tailwind::BLUE.c400,
+        tailwind::ORANGE.c500,
+        tailwind::PINK.c400,
+    ];
+
+    pub fn new(breakdown: &'a CpuBreakdown) -> Self {
+        Self { breakdown }
+    }
+
+    fn spans(breakdown: &CpuBreakdown, width: usize) -> Vec<Span<'static>> {
+        let segments = breakdown.segments().into_iter().zip(Self::COLORS);
+        stacked_spans(segments.map(|((_, value), color)| (value / 100., color)), width)
+    }
+
+    fn legend(&self) -> Line<'static> {
+        let spans = self
+            .breakdown
+            .segments()
+            .into_iter()
+            .zip(Self::COLORS)
+            .flat_map(|((label, value), color)| {
+                [format!("{label} ").fg(color), format!("{value:.0} ").into()]
+            })
+            .collect::<Vec<_>>();
+        Line::from(spans)
+    }
+}
+
+impl Widget for CpuBreakdownBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        Line::from(Self::spans(self.breakdown, area.width as usize)).render(area, buf);
+        if area.height >= 2 {
+            let legend = Rect::new(area.x, area.y + 1, area.width, 1);
+            self.legend().render(legend, buf);
+        }
+    }
+}
+
+/// マウントごとに1行のゲージを縦に並べる。入りきらない分は省く
+pub struct DiskGauges<'a> {
+    disks: &'a [DiskInfo],
+}
+
+impl<'a> DiskGauges<'a> {
+    const MOUNT_WIDTH: usize = 16;
+    /// `ext4 SSD ⏏`
+    const META_WIDTH: usize = 11;
+    /// `123.4G/123.4G 100%`
+    const SIZE_WIDTH: usize = 19;
+
+    pub fn new(disks: &'a [DiskInfo]) -> Self {
+        Self { disks }
+    }
+
+    fn color(usage: f64) -> Color {
+        match usage {
+            u if u >= 90. => tailwind::RED.c500,
+            u if u >= 75. => tailwind::YELLOW.c500,
+            _ => tailwind::GREEN.c500,
+        }
+    }
+
+    /// 長いマウントポイントは末尾を残して縮める
+    fn mount(disk: &DiskInfo) -> String {
+        let mount = disk.mount_point.display().to_string();
+        let len = mount.chars().count();
+        if len <= Self::MOUNT_WIDTH {
+            return mount;
+        }
+        let tail = mount.chars().skip(len - (Self::MOUNT_WIDTH - 1)).collect::<String>();
+        format!("…{tail}")
+    }
+
+    fn line(disk: &DiskInfo, width: usize) -> Line<'static> {
+        let usage = disk.usage();
+        let meta = format!(
+            "{} {}{}",
+            disk.file_system,
+            disk.kind,
+            if disk.removable { " ⏏" } else { "" }
+        );
+        let bar_width = width
+            .saturating_sub(Self::MOUNT_WIDTH + 1 + Self::META_WIDTH + Self::SIZE_WIDTH);
+        let filled = ((usage / 100.) * bar_width as f64).round() as usize;
+        Line::from(vec![
+            format!("{:<w$} ", Self::mount(disk), w = Self::MOUNT_WIDTH).bold(),
+            format!("{:<w$.w$}", meta, w = Self::META_WIDTH).fg(tailwind::SLATE.c400),
+            "█".repeat(filled).fg(Self::color(usage)),
+            "░".repeat(bar_width - filled).fg(tailwind::SLATE.c700),
+            format!(
+                " {:>w$}",
+                format!(
+                    "{}/{} {:>3.0}%",
+                    DisplayBytes::new(disk.used()),
+                    DisplayBytes::new(disk.total),
+                    usage
+                ),
+                w = Self::SIZE_WIDTH - 1
+            )
+            .into(),
+        ])
+    }
+}
+
+impl Widget for DiskGauges<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if self.disks.is_empty() {
+            Line::from("No disks".dark_gray()).render(area, buf);
+            return;
+        }
+        for (disk, y) in self.disks.iter().zip(area.top()..area.bottom()) {
+            Self::line(disk, area.width as usize).render(Rect::new(area.x, y, area.width, 1), buf);
+        }
+    }
+}
+
+/// used/buffers/cacheを積み上げた棒と凡例。回収できるキャッシュを使用中と区別する
+pub struct MemoryBar<'a> {
+    info: &'a MemInfo,
+}
+
+impl<'a> MemoryBar<'a> {
+    /// `MemInfo::segments`と同じ順
+    const COLORS: [Color; 3] = [
+        tailwind::GREEN.c500,
+        tailwind::BLUE.c400,
+        tailwind::YELLOW.c400,
+    ];
+
+    pub fn new(info: &'a MemInfo) -> Self {
+        Self { info }
+    }
+
+    fn legend(&self) -> Line<'static> {
+        let mut spans = self
+            .info
+            .segments()
+            .into_iter()
+            .zip(Self::COLORS)
+            .flat_map(|((label, bytes), color)| {
+                [
+                    format!("{label} ").fg(color),
+                    format!("{} ", DisplayBytes::new(bytes)).into(),
+                ]
+            })
+            .collect::<Vec<_>>();
+        spans.push("avail ".fg(tailwind::SLATE.c400));
+        spans.push(DisplayBytes::new(self.info.available).to_string().into());
+        Line::from(spans)
+    }
+}
+
+impl Widget for MemoryBar<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() || self.info.total == 0 {
+            return;
+        }
+        let total = self.info.total as f64;
+        let segments = self.info.segments().into_iter().zip(Self::COLORS);
+        let spans = stacked_spans(
+            segments.map(|((_, bytes), color)| (bytes as f64 / total, color)),
+            area.width as usize,
+        );
+        Line::from(spans).render(area, buf);
+        if area.height >= 2 {
+            let legend = Rect::new(area.x, area.y + 1, area.width, 1);
+            self.legend().render(legend, buf);
+        }
+    }
+}
+
+/// /proc/meminfoの詳細表の行。名前、サイズ、全体に対する割合
+fn meminfo_rows(info: &MemInfo) -> Vec<(&'static str, String, String)> {
+    let percent = |bytes: u64| {
+        if info.total == 0 {
+            "-".to_owned()
+        } else {
+            format!("{:.1}%", bytes as f64 / info.total as f64 * 100.)
+        }
+    };
+    let size = |bytes: u64| DisplayBytes::new(bytes).to_string();
+    let mut rows = [
+        ("Total", info.total),
+        ("Used", info.used()),
+        ("Free", info.free),
+        ("Available", info.available),
+        ("Buffers", info.buffers),
+        ("Page Cache", info.cached),
+        ("Shared", info.shared),
+        ("Slab Reclaimable", info.slab_reclaimable),
+        ("Slab Unreclaimable", info.slab_unreclaimable),
+        ("Dirty", info.dirty),
+        ("Writeback", info.writeback),
+    ]
+    .into_iter()
+    .map(|(name, bytes)| (name, size(bytes), percent(bytes)))
+    .collect::<Vec<_>>();
+
+    let huge_page = |pages: u64| pages * info.huge_page_size;
+    rows.push((
+        "Huge Pages",
+        format!(
+            "{} ({}/{} free)",
+            size(huge_page(info.huge_pages_total)),
+            info.huge_pages_free,
+            info.huge_pages_total
+        ),
+        percent(huge_page(info.huge_pages_total)),
+    ));
+    // コミット量は上限に対する割合の方が意味がある
+    rows.push((
+        "Committed",
+        format!("{} / {}", size(info.committed_as), size(info.commit_limit)),
+        if info.commit_limit == 0 {
+            "-".to_owned()
+        } else {
+            format!(
+                "{:.1}%",
+                info.committed_as as f64 / info.commit_limit as f64 * 100.
+            )
+        },
+    ));
+    rows
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessViewMode {
+    #[default]
+    Flat,
+    Tree,
+    /// ユーザーやコマンドごとの合計
+    Group(GroupBy),
+}
+
 #[derive(Debug, Default)]
-pub struct ProcessTab  {
-    pub process_text:Text<'static>,
+pub struct ProcessTab {
+    pub rows: Vec<ProcessRow>,
+    pub sort: ProcessSort,
+    pub filter: ProcessFilter,
+    pub total: usize,
+    pub mode: ProcessViewMode,
+    /// ツリー表示時の罫線。`rows`と同じ並び
+    tree_prefixes: Vec<String>,
+    collapsed: HashSet<Pid>,
+    /// 集計表示時の行。このとき`rows`は空
+    groups: Vec<ProcessGroup>,
+    /// PSS・USS・スワップの列を出す
+    pub show_memory: bool,
+    /// 直前に描画した時の表示行数
+    page_height: u16,
 }
 
 impl ProcessTab {
-    fn new() -> Self {
-        Self {
-            process_text:"test".into(),
+    const HIGHLIGHT_SYMBOL: &'static str = "> ";
+    const COLUMN_SPACING: u16 = 1;
+    /// 画面が狭い時はこの順に列を残す
+    const PRIORITY: [ProcessColumn; 18] = [
+        ProcessColumn::Pid,
+        ProcessColumn::Name,
+        ProcessColumn::Cpu,
+        ProcessColumn::Rss,
+        ProcessColumn::Pss,
+        ProcessColumn::Uss,
+        ProcessColumn::Swap,
+        ProcessColumn::User,
+        ProcessColumn::Command,
+        ProcessColumn::Status,
+        ProcessColumn::ReadRate,
+        ProcessColumn::WriteRate,
+        ProcessColumn::Cgroup,
+        ProcessColumn::Virt,
+        ProcessColumn::Ppid,
+        ProcessColumn::Start,
+        ProcessColumn::DiskRead,
+        ProcessColumn::DiskWrite,
+    ];
+
+    /// 絞り込みと並び替えをして行を差し替える。選択中のプロセスは位置が変わっても選択し続ける
+    pub fn set_rows(&mut self, mut rows: Vec<ProcessRow>, state: &mut TableState) {
+        let selected_pid = self.selected(state).map(|row| row.pid);
+        let selected_group = self.selected_group(state).map(|group| group.key.clone());
+        self.total = rows.len();
+        self.tree_prefixes.clear();
+        self.groups.clear();
+        match self.mode {
+            ProcessViewMode::Flat => {
+                rows.retain(|row| self.filter.matches(row));
+                self.sort.apply(&mut rows);
+                self.rows = rows;
+            }
+            ProcessViewMode::Tree => {
+                self.collapsed
+                    .retain(|pid| rows.iter().any(|row| row.pid == *pid));
+                (self.rows, self.tree_prefixes) =
+                    build_tree(&rows, &self.filter, &self.sort, &self.collapsed)
+                        .into_iter()
+                        .map(|tree_row| (tree_row.row, tree_row.prefix))
+                        .unzip();
+            }
+            ProcessViewMode::Group(by) => {
+                self.groups = aggregate(&rows, by, &self.filter, &self.sort);
+                self.rows.clear();
+            }
+        }
+
+        let idx = match self.mode {
+            ProcessViewMode::Group(_) => selected_group
+                .and_then(|key| self.groups.iter().position(|group| group.key == key)),
+            _ => selected_pid.and_then(|pid| self.rows.iter().position(|row| row.pid == pid)),
+        };
+        let len = self.len();
+        match idx.or(state.selected()) {
+            _ if len == 0 => state.select(None),
+            Some(idx) => state.select(Some(idx.min(len - 1))),
+            None => {}
+        }
+    }
+
+    /// 表示中の行数
+    pub fn len(&self) -> usize {
+        match self.mode {
+            ProcessViewMode::Group(_) => self.groups.len(),
+            _ => self.rows.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn render_filter_bar(&self, area: Rect, buf: &mut Buffer, editing: bool) {
+        let filter = &self.filter;
+        let mut spans = vec![
+            "/".blue().bold(),
+            Span::raw(filter.get_query().to_owned()),
+        ];
+        if editing {
+            spans.push("▏".slow_blink());
+        }
+        if filter.is_regex() {
+            spans.push(" [regex]".yellow());
+        }
+        if filter.is_ignore_case() {
+            spans.push(" [ignore case]".yellow());
+        }
+        if let Some(e) = filter.get_error() {
+            // 正規表現のエラーは複数行なので最後の行だけ出す
+            let e = e.lines().last().unwrap_or(e).trim().to_owned();
+            spans.push(format!(" {e}").red());
+        }
+
+        Paragraph::new(Line::from(spans)).render(area, buf);
+    }
+
+    fn title(&self) -> String {
+        let mode = match self.mode {
+            ProcessViewMode::Flat => "",
+            ProcessViewMode::Tree => " Tree",
+            ProcessViewMode::Group(GroupBy::User) => " by User",
+            ProcessViewMode::Group(GroupBy::Command) => " by Command",
+            ProcessViewMode::Group(GroupBy::Cgroup) => " by Cgroup",
+        };
+        if let ProcessViewMode::Group(_) = self.mode {
+            format!(" Process{mode} ({} groups/{}) ", self.groups.len(), self.total)
+        } else if self.filter.is_active() {
+            format!(" Process{mode} ({}/{}) ", self.rows.len(), self.total)
+        } else {
+            format!(" Process{mode} ({}) ", self.total)
         }
     }
+
+    pub fn toggle_mode(&mut self) {
+        self.mode = match self.mode {
+            ProcessViewMode::Tree => ProcessViewMode::Flat,
+            _ => ProcessViewMode::Tree,
+        };
+    }
+
+    /// 一覧 → ユーザー別 → コマンド別 → cgroup別 → 一覧の順に切り替える
+    pub fn cycle_group(&mut self) {
+        self.mode = match self.mode {
+            ProcessViewMode::Group(GroupBy::User) => ProcessViewMode::Group(GroupBy::Command),
+            ProcessViewMode::Group(GroupBy::Command) => ProcessViewMode::Group(GroupBy::Cgroup),
+            ProcessViewMode::Group(GroupBy::Cgroup) => ProcessViewMode::Flat,
+            _ => ProcessViewMode::Group(GroupBy::User),
+        };
+    }
+
+    /// 選択中のプロセスの子を折りたたむ・展開する
+    pub fn toggle_collapsed(&mut self, state: &TableState) {
+        if self.mode != ProcessViewMode::Tree {
+            return;
+        }
+        if let Some(pid) = self.selected(state).map(|row| row.pid) {
+            if !self.collapsed.remove(&pid) {
+                self.collapsed.insert(pid);
+            }
+        }
+    }
+
+    pub fn toggle_memory(&mut self) {
+        self.show_memory = !self.show_memory;
+        if !self.show_memory && self.sorts_by_memory() {
+            self.sort = ProcessSort::default();
+        }
+    }
+
+    /// 隠れている列は飛ばして次の列で並べる
+    pub fn next_sort_column(&mut self) {
+        self.sort.next_column();
+        while !self.show_memory && self.sort.column.is_memory_detail() {
+            self.sort.next_column();
+        }
+    }
+
+    /// PSSなどで並べる時は全プロセス分を読む必要がある
+    pub fn sorts_by_memory(&self) -> bool {
+        self.sort.column.is_memory_detail()
+    }
+
+    /// 枠と見出しを除いた行数を覚えておく
+    pub fn set_page_height(&mut self, area_height: u16) {
+        self.page_height = area_height.saturating_sub(3);
+    }
+
+    /// 画面に出ている行と選択中の行
+    pub fn visible_rows(&self, state: &TableState) -> Vec<ProcessRow> {
+        let mut rows = self
+            .rows
+            .iter()
+            .skip(state.offset())
+            .take(self.page_height as usize)
+            .cloned()
+            .collect::<Vec<_>>();
+        if let Some(selected) = self.selected(state) {
+            if !rows.iter().any(|row| row.pid == selected.pid) {
+                rows.push(selected.clone());
+            }
+        }
+        rows
+    }
+
+    pub fn set_memory(&mut self, mut memory: HashMap<Pid, MemoryBreakdown>) {
+        for row in self.rows.iter_mut() {
+            if let Some(breakdown) = memory.remove(&row.pid) {
+                row.memory = Some(breakdown);
+            }
+        }
+    }
+
+    pub fn selected(&self, state: &TableState) -> Option<&ProcessRow> {
+        state.selected().and_then(|idx| self.rows.get(idx))
+    }
+
+    fn selected_group(&self, state: &TableState) -> Option<&ProcessGroup> {
+        state.selected().and_then(|idx| self.groups.get(idx))
+    }
+
+    fn header(&self, column: ProcessColumn) -> String {
+        if column == self.sort.column {
+            format!("{column}{}", self.sort.order.symbol())
+        } else {
+            column.to_string()
+        }
+    }
+
+    fn column_width(column: ProcessColumn) -> u16 {
+        match column {
+            ProcessColumn::Pid | ProcessColumn::Ppid => 7,
+            ProcessColumn::User => 10,
+            ProcessColumn::Status => 2,
+            ProcessColumn::Cpu => 6,
+            ProcessColumn::Rss
+            | ProcessColumn::Pss
+            | ProcessColumn::Uss
+            | ProcessColumn::Swap
+            | ProcessColumn::Virt
+            | ProcessColumn::ReadRate
+            | ProcessColumn::WriteRate
+            | ProcessColumn::DiskRead
+            | ProcessColumn::DiskWrite => 8,
+            ProcessColumn::Start => 8,
+            ProcessColumn::Name => 16,
+            ProcessColumn::Command | ProcessColumn::Cgroup => 20,
+        }
+    }
+
+    /// 表示幅に収まる列を表示順で返す。
+    /// 優先度の高い列が入らなければそこで止め、低い列が代わりに入り込まないようにする
+    pub fn visible_columns(width: u16, show_memory: bool) -> Vec<ProcessColumn> {
+        let mut remaining = width.saturating_sub(Self::HIGHLIGHT_SYMBOL.len() as u16);
+        let mut fitted = vec![];
+        for column in Self::PRIORITY
+            .into_iter()
+            .filter(|column| show_memory || !column.is_memory_detail())
+        {
+            let needed = Self::column_width(column) + Self::COLUMN_SPACING;
+            if needed > remaining {
+                break;
+            }
+            remaining -= needed;
+            fitted.push(column);
+        }
+
+        ProcessColumn::iter()
+            .filter(|column| fitted.contains(column))
+            .collect()
+    }
+
+    fn constraint(column: ProcessColumn) -> Constraint {
+        match column {
+            ProcessColumn::Command => Constraint::Fill(1),
+            _ => Constraint::Length(Self::column_width(column)),
+        }
+    }
+
+    fn cell(column: ProcessColumn, text: String) -> Cell<'static> {
+        if column.is_numeric() {
+            Cell::from(Text::from(text).alignment(Alignment::Right))
+        } else {
+            Cell::from(text)
+        }
+    }
+
+    /// 集計表示で合計を出す列
+    const GROUP_COLUMNS: [ProcessColumn; 5] = [
+        ProcessColumn::Cpu,
+        ProcessColumn::Rss,
+        ProcessColumn::ReadRate,
+        ProcessColumn::WriteRate,
+        ProcessColumn::Virt,
+    ];
+    const GROUP_COUNT_WIDTH: u16 = 6;
+
+    fn group_table(&self, block: Block<'static>) -> Table<'static> {
+        let key_header = match self.mode {
+            ProcessViewMode::Group(by) => by.to_string().to_uppercase(),
+            _ => String::new(),
+        };
+        // 使用量以外の列で並べている時はキーかプロセス数で並んでいる
+        let (key_header, count_header) = match self.sort.column {
+            column if Self::GROUP_COLUMNS.contains(&column) => (key_header, "PROCS".to_owned()),
+            ProcessColumn::Name
+            | ProcessColumn::User
+            | ProcessColumn::Command
+            | ProcessColumn::Cgroup => (
+                format!("{key_header}{}", self.sort.order.symbol()),
+                "PROCS".to_owned(),
+            ),
+            _ => (key_header, format!("PROCS{}", self.sort.order.symbol())),
+        };
+
+        let header = Row::new(
+            [Cell::from(key_header), Self::cell(ProcessColumn::Pid, count_header)]
+                .into_iter()
+                .chain(
+                    Self::GROUP_COLUMNS
+                        .iter()
+                        .map(|column| Self::cell(*column, self.header(*column))),
+                ),
+        )
+        .style(Style::new().bold().fg(tailwind::BLUE.c400));
+
+        let rows = self.groups.iter().map(|group| {
+            Row::new(
+                [
+                    Cell::from(group.key.clone()),
+                    Self::cell(ProcessColumn::Pid, group.count.to_string()),
+                ]
+                .into_iter()
+                .chain(
+                    Self::GROUP_COLUMNS
+                        .iter()
+                        .map(|column| Self::cell(*column, column.cell(&group.total))),
+                ),
+            )
+        });
+
+        let constraints = [
+            Constraint::Fill(1),
+            Constraint::Length(Self::GROUP_COUNT_WIDTH),
+        ]
+        .into_iter()
+        .chain(
+            Self::GROUP_COLUMNS
+                .iter()
+                .map(|column| Self::constraint(*column)),
+        );
+        Table::new(rows, constraints).header(header).block(block)
+    }
 }
 
 impl StatefulWidgetRef for ProcessTab {
-    type State = ScrollViewState;
+    type State = TableState;
+
+    fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let block = Block::bordered()
+            .border_set(border::THICK)
+            .title(self.title())
+            .title_alignment(Alignment::Center);
+        if let ProcessViewMode::Group(_) = self.mode {
+            let table = self
+                .group_table(block)
+                .column_spacing(Self::COLUMN_SPACING)
+                .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+                .highlight_symbol(Self::HIGHLIGHT_SYMBOL);
+            StatefulWidget::render(table, area, buf, state);
+            return;
+        }
+        let columns = Self::visible_columns(block.inner(area).width, self.show_memory);
+
+        let header = Row::new(
+            columns
+                .iter()
+                .map(|column| Self::cell(*column, self.header(*column))),
+        )
+        .style(Style::new().bold().fg(tailwind::BLUE.c400));
+
+        let rows = self.rows.iter().enumerate().map(|(idx, row)| {
+            Row::new(columns.iter().map(|column| {
+                let text = match (column, self.tree_prefixes.get(idx)) {
+                    (ProcessColumn::Command, Some(prefix)) => format!("{prefix}{}", row.cmd),
+                    _ => column.cell(row),
+                };
+                Self::cell(*column, text)
+            }))
+        });
+
+        let table = Table::new(rows, columns.iter().map(|c| Self::constraint(*c)))
+            .header(header)
+            .block(block)
+            .column_spacing(Self::COLUMN_SPACING)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol(Self::HIGHLIGHT_SYMBOL);
+
+        StatefulWidget::render(table, area, buf, state);
+    }
+}
+
+/// プロセスの起動・終了の履歴。新しいものを上に出す
+#[derive(Debug, Default)]
+pub struct EventTab {
+    log: EventLog,
+}
+
+impl EventTab {
+    const WIDTHS: [Constraint; 7] = [
+        Constraint::Length(8),
+        Constraint::Length(5),
+        Constraint::Length(7),
+        Constraint::Length(16),
+        Constraint::Length(10),
+        Constraint::Length(7),
+        Constraint::Fill(1),
+    ];
+
+    /// 選択中のイベントが押し下げられても同じ行を選び続ける
+    pub fn push_events(&mut self, events: Vec<ProcessEvent>, state: &mut TableState) {
+        let added = events.len();
+        events.into_iter().for_each(|event| self.log.push(event));
+        if let Some(idx) = state.selected() {
+            state.select(Some((idx + added).min(self.log.len().saturating_sub(1))));
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.log.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.log.is_empty()
+    }
+
+    fn row(event: &ProcessEvent) -> Row<'static> {
+        use chrono::{DateTime, Local};
+
+        let at = DateTime::<Local>::from(event.at).format("%H:%M:%S");
+        let kind = match event.kind {
+            EventKind::Started => event.kind.to_string().green(),
+            EventKind::Exited => event.kind.to_string().red(),
+            EventKind::OomKill => event.kind.to_string().white().on_red().bold(),
+        };
+        let runtime = event
+            .runtime
+            .map(|runtime| DisplayDuration::new(runtime).to_string())
+            .unwrap_or_default();
+        let detail = match (event.cause, &event.cgroup) {
+            (Some(cause), _) => format!("[{cause}] {}", event.cmd),
+            (None, Some(cgroup)) => format!("[{cgroup}] {}", event.cmd),
+            (None, None) => event.cmd.clone(),
+        };
+        // OOM killの犠牲が分からなかった時
+        let pid = match event.pid.as_u32() {
+            0 => "-".to_owned(),
+            pid => pid.to_string(),
+        };
+
+        Row::new([
+            Cell::from(at.to_string()),
+            Cell::from(kind),
+            Cell::from(Text::from(pid).alignment(Alignment::Right)),
+            Cell::from(event.name.clone()),
+            Cell::from(event.user.clone()),
+            Cell::from(Text::from(runtime).alignment(Alignment::Right)),
+            Cell::from(detail),
+        ])
+    }
+}
+
+impl StatefulWidgetRef for EventTab {
+    type State = TableState;
 
     fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
-        const SCROLLVIEW_HEIGHT: u16 = 80;
-        let mut scroll_view = ScrollView::new((area.width - 1, SCROLLVIEW_HEIGHT).into());
-        scroll_view.render_widget(
-            Paragraph::new(self.process_text.clone())
-                .white()
-                .on_green()
-                .wrap(Wrap::default()),
-            Rect::new(0, 0, area.width-1, SCROLLVIEW_HEIGHT),
+        let block = Block::bordered()
+            .border_set(border::THICK)
+            .title(format!(" Events ({}) ", self.log.len()))
+            .title_alignment(Alignment::Center);
+
+        let header = Row::new(["TIME", "EVENT", "PID", "NAME", "USER", "RUNTIME", "COMMAND"])
+            .style(Style::new().bold().fg(tailwind::BLUE.c400));
+
+        let table = Table::new(self.log.iter().map(Self::row), Self::WIDTHS)
+            .header(header)
+            .block(block)
+            .column_spacing(ProcessTab::COLUMN_SPACING)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol(ProcessTab::HIGHLIGHT_SYMBOL);
+
+        StatefulWidget::render(table, area, buf, state);
+    }
+}
+
+/// `ss -tulpn`のようなソケット一覧
+#[derive(Debug, Default)]
+pub struct PortTab {
+    sockets: Vec<SocketEntry>,
+    /// 絞り込み前の件数
+    total: usize,
+    /// 待ち受け以外の接続も出す
+    pub show_all: bool,
+    pub show_unix: bool,
+}
+
+impl PortTab {
+    const WIDTHS: [Constraint; 5] = [
+        Constraint::Length(5),
+        Constraint::Length(10),
+        Constraint::Length(30),
+        Constraint::Length(30),
+        Constraint::Fill(1),
+    ];
+
+    /// 選択中のソケットはinodeで追いかける
+    pub fn set_sockets(&mut self, mut sockets: Vec<SocketEntry>, state: &mut TableState) {
+        let selected = state
+            .selected()
+            .and_then(|idx| self.sockets.get(idx))
+            .map(|socket| socket.inode);
+        self.total = sockets.len();
+        sockets.retain(|socket| {
+            (self.show_unix || socket.protocol.is_inet()) && (self.show_all || socket.is_listening())
+        });
+        self.sockets = sockets;
+
+        let idx = selected.and_then(|inode| self.sockets.iter().position(|s| s.inode == inode));
+        match idx.or(state.selected()) {
+            _ if self.sockets.is_empty() => state.select(None),
+            Some(idx) => state.select(Some(idx.min(self.sockets.len() - 1))),
+            None => {}
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sockets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sockets.is_empty()
+    }
+
+    fn row(socket: &SocketEntry) -> Row<'static> {
+        let owners = socket
+            .owners
+            .iter()
+            .map(|owner| format!("{}({})", owner.name, owner.pid))
+            .collect::<Vec<_>>()
+            .join(",");
+        Row::new([
+            socket.protocol.to_string(),
+            socket.state.to_owned(),
+            socket.local.clone(),
+            socket.remote.clone(),
+            owners,
+        ])
+    }
+}
+
+impl StatefulWidgetRef for PortTab {
+    type State = TableState;
+
+    fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let mode = if self.show_all { "All" } else { "Listening" };
+        let block = Block::bordered()
+            .border_set(border::THICK)
+            .title(format!(" Ports {mode} ({}/{}) ", self.sockets.len(), self.total))
+            .title_alignment(Alignment::Center);
+
+        let header = Row::new(["PROTO", "STATE", "LOCAL", "PEER", "PROCESS"])
+            .style(Style::new().bold().fg(tailwind::BLUE.c400));
+
+        let table = Table::new(self.sockets.iter().map(Self::row), Self::WIDTHS)
+            .header(header)
+            .block(block)
+            .column_spacing(ProcessTab::COLUMN_SPACING)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol(ProcessTab::HIGHLIGHT_SYMBOL);
+
+        StatefulWidget::render(table, area, buf, state);
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SensorTab {
+    sensors: Vec<SensorReading>,
+}
+
+impl SensorTab {
+    const WIDTHS: [Constraint; 6] = [
+        Constraint::Length(16),
+        Constraint::Fill(1),
+        Constraint::Length(4),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(10),
+    ];
+
+    pub fn set_sensors(&mut self, sensors: Vec<SensorReading>, state: &mut TableState) {
+        self.sensors = sensors;
+        match state.selected() {
+            _ if self.sensors.is_empty() => state.select(None),
+            Some(idx) => state.select(Some(idx.min(self.sensors.len() - 1))),
+            None => {}
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sensors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sensors.is_empty()
+    }
+
+    fn value(kind: SensorKind, value: Option<f64>) -> String {
+        match (kind, value) {
+            (_, None) => "-".into(),
+            (SensorKind::Temperature, Some(value)) => format!("{value:.1}°C"),
+            (SensorKind::Fan, Some(value)) => format!("{value:.0} RPM"),
+        }
+    }
+
+    fn color(level: SensorLevel) -> Style {
+        match level {
+            SensorLevel::Normal => Style::new().fg(tailwind::GREEN.c500),
+            SensorLevel::Warm => Style::new().fg(tailwind::YELLOW.c400),
+            SensorLevel::Hot => Style::new().fg(tailwind::ORANGE.c500),
+            SensorLevel::Critical => Style::new().fg(tailwind::RED.c500).bold(),
+        }
+    }
+
+    fn row(sensor: &SensorReading) -> Row<'static> {
+        Row::new([
+            Cell::from(sensor.chip.clone()),
+            Cell::from(sensor.label.clone()),
+            Cell::from(sensor.kind.to_string()),
+            Cell::from(Self::value(sensor.kind, Some(sensor.value)))
+                .style(Self::color(sensor.level())),
+            Cell::from(Self::value(sensor.kind, sensor.limit)),
+            Cell::from(Self::value(sensor.kind, sensor.critical)),
+        ])
+    }
+}
+
+impl StatefulWidgetRef for SensorTab {
+    type State = TableState;
+
+    fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let block = Block::bordered()
+            .border_set(border::THICK)
+            .title(format!(" Sensors ({}) ", self.sensors.len()))
+            .title_alignment(Alignment::Center);
+
+        // 仮想マシンなどではhwmonが1つもない
+        if self.sensors.is_empty() {
+            Paragraph::new("No temperature or fan sensors found".dark_gray())
+                .alignment(Alignment::Center)
+                .block(block)
+                .render(area, buf);
+            return;
+        }
+
+        let header = Row::new(["CHIP", "SENSOR", "TYPE", "VALUE", "HIGH/MIN", "CRIT"])
+            .style(Style::new().bold().fg(tailwind::BLUE.c400));
+
+        let table = Table::new(self.sensors.iter().map(Self::row), Self::WIDTHS)
+            .header(header)
+            .block(block)
+            .column_spacing(ProcessTab::COLUMN_SPACING)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol(ProcessTab::HIGHLIGHT_SYMBOL);
+
+        StatefulWidget::render(table, area, buf, state);
+    }
+}
+
+/// ブロックデバイスごとのスループット、IOPS、待ち時間、利用率
+#[derive(Debug, Default)]
+pub struct DiskIoTab {
+    devices: Vec<DiskIo>,
+}
+
+impl DiskIoTab {
+    const WIDTHS: [Constraint; 7] = [
+        Constraint::Fill(1),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(8),
+        Constraint::Length(8),
+        Constraint::Length(9),
+        Constraint::Length(6),
+    ];
+
+    /// デバイスの増減があっても同じデバイスを選び続ける
+    pub fn set_devices(&mut self, devices: Vec<DiskIo>, state: &mut TableState) {
+        let selected = state
+            .selected()
+            .and_then(|idx| self.devices.get(idx))
+            .map(|device| device.name.clone());
+        self.devices = devices;
+        let idx = selected.and_then(|name| self.devices.iter().position(|d| d.name == name));
+        match idx.or(state.selected()) {
+            _ if self.devices.is_empty() => state.select(None),
+            Some(idx) => state.select(Some(idx.min(self.devices.len() - 1))),
+            None => {}
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.devices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+
+    /// グラフを出すデバイス。未選択なら先頭
+    pub fn selected(&self, state: &TableState) -> Option<&DiskIo> {
+        self.devices.get(state.selected().unwrap_or_default())
+    }
+
+    fn util_color(util: f64) -> Color {
+        match util {
+            u if u >= 90. => tailwind::RED.c500,
+            u if u >= 60. => tailwind::YELLOW.c400,
+            _ => tailwind::GREEN.c500,
+        }
+    }
+
+    fn row(device: &DiskIo) -> Row<'static> {
+        let right = |text: String| Cell::from(Text::from(text).alignment(Alignment::Right));
+        let rate = |bytes: u64| right(format!("{}/s", DisplayBytes::new(bytes)));
+        Row::new([
+            Cell::from(device.name.clone()),
+            rate(device.read_bytes),
+            rate(device.write_bytes),
+            right(format!("{:.0}", device.read_iops)),
+            right(format!("{:.0}", device.write_iops)),
+            right(format!("{:.2}ms", device.await_ms)),
+            right(format!("{:.0}%", device.util)).style(Self::util_color(device.util)),
+        ])
+    }
+}
+
+impl StatefulWidgetRef for DiskIoTab {
+    type State = TableState;
+
+    fn render_ref(&self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let block = Block::bordered()
+            .border_set(border::THICK)
+            .title(format!(" Disk I/O ({}) ", self.devices.len()))
+            .title_alignment(Alignment::Center);
+
+        // 最初の更新までは差分が取れない
+        if self.devices.is_empty() {
+            Paragraph::new("No block device activity".dark_gray())
+                .alignment(Alignment::Center)
+                .block(block)
+                .render(area, buf);
+            return;
+        }
+
+        let header = Row::new(["DEVICE", "READ", "WRITE", "R IOPS", "W IOPS", "AWAIT", "UTIL"])
+            .style(Style::new().bold().fg(tailwind::BLUE.c400));
+
+        let table = Table::new(self.devices.iter().map(Self::row), Self::WIDTHS)
+            .header(header)
+            .block(block)
+            .column_spacing(ProcessTab::COLUMN_SPACING)
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol(ProcessTab::HIGHLIGHT_SYMBOL);
+
+        StatefulWidget::render(table, area, buf, state);
+    }
+}
+
+/// 操作対象のプロセス
+#[derive(Debug, Clone)]
+pub struct ProcessTarget {
+    pub pid: Pid,
+    pub name: String,
+}
+
+impl From<&ProcessRow> for ProcessTarget {
+    fn from(row: &ProcessRow) -> Self {
+        Self {
+            pid: row.pid,
+            name: row.name.clone(),
+        }
+    }
+}
+
+/// 表示中はキー入力をすべて受け取る
+#[derive(Debug)]
+pub enum Popup {
+    SignalMenu {
+        target: ProcessTarget,
+        selected: usize,
+    },
+    SignalNumber {
+        target: ProcessTarget,
+        input: String,
+    },
+    ConfirmSignal {
+        target: ProcessTarget,
+        signal: ProcessSignal,
+    },
+    Detail {
+        target: ProcessTarget,
+        detail: Box<ProcessDetail>,
+        scroll: ScrollViewState,
+    },
+    /// 開いている間は更新のたびに読み直す
+    Memory(Box<MemInfo>),
+    Swap(Box<SwapSummary>),
+}
+
+impl Popup {
+    /// メニューの最後は番号入力
+    pub const SIGNAL_MENU_LEN: usize = ProcessSignal::MENU.len() + 1;
+
+    fn area(area: Rect, width: u16, height: u16) -> Rect {
+        let [area] = Layout::horizontal([Constraint::Length(width)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::vertical([Constraint::Length(height)])
+            .flex(Flex::Center)
+            .areas(area);
+        area
+    }
+
+    fn block(title: String) -> Block<'static> {
+        Block::bordered()
+            .border_set(border::THICK)
+            .title(title)
+            .title_alignment(Alignment::Center)
+            .fg(tailwind::SLATE.c200)
+            .bg(tailwind::SLATE.c900)
+    }
+}
+
+impl Widget for &mut Popup {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        match self {
+            Popup::SignalMenu { target, selected } => {
+                let area = Popup::area(area, 40, Popup::SIGNAL_MENU_LEN as u16 + 2);
+                let items = ProcessSignal::MENU
+                    .iter()
+                    .map(ProcessSignal::to_string)
+                    .chain(["Other...".to_owned()]);
+                let list = List::new(items)
+                    .block(Popup::block(format!(" Signal {} ({}) ", target.name, target.pid)))
+                    .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+                Clear.render(area, buf);
+                StatefulWidget::render(
+                    list,
+                    area,
+                    buf,
+                    &mut ListState::default().with_selected(Some(*selected)),
+                );
+            }
+            Popup::SignalNumber { target, input } => {
+                let area = Popup::area(area, 40, 3);
+                Clear.render(area, buf);
+                Paragraph::new(Line::from(vec![
+                    "Signal: ".bold(),
+                    Span::raw(input.clone()),
+                    "▏".slow_blink(),
+                ]))
+                .block(Popup::block(format!(" Signal {} ({}) ", target.name, target.pid)))
+                .render(area, buf);
+            }
+            Popup::ConfirmSignal { target, signal } => {
+                let area = Popup::area(area, 50, 4);
+                Clear.render(area, buf);
+                Paragraph::new(Text::from(vec![
+                    Line::from(vec![
+                        "Send ".into(),
+                        signal.to_string().red().bold(),
+                        format!(" to {} ({})?", target.name, target.pid).into(),
+                    ]),
+                    Line::from(vec!["<Y>".red().bold(), " Yes  ".into(), "<N>".red().bold(), " No".into()]),
+                ]))
+                .alignment(Alignment::Center)
+                .block(Popup::block(" Confirm ".into()))
+                .render(area, buf);
+            }
+            Popup::Detail {
+                target,
+                detail,
+                scroll,
+            } => {
+                let area = area.inner(ratatui::layout::Margin::new(2, 1));
+                let block = Popup::block(format!(" {} ({}) ", target.name, target.pid));
+                let inner = block.inner(area);
+                Clear.render(area, buf);
+                block.render(area, buf);
+
+                // スクロールバーの分だけ狭くする
+                let width = inner.width.saturating_sub(1);
+                let paragraph = Paragraph::new(detail_text(detail)).wrap(Wrap { trim: false });
+                let height = paragraph.line_count(width) as u16;
+                let mut scroll_view = ScrollView::new((width, height).into());
+                scroll_view.render_widget(paragraph, Rect::new(0, 0, width, height));
+                scroll_view.render(inner, buf, scroll);
+            }
+            Popup::Memory(info) => {
+                let rows = meminfo_rows(info);
+                let area = Popup::area(area, 60, rows.len() as u16 + 3);
+                let header = Row::new(["", "SIZE", "% OF TOTAL"])
+                    .style(Style::new().bold().fg(tailwind::BLUE.c400));
+                let widths = [
+                    Constraint::Length(20),
+                    Constraint::Fill(1),
+                    Constraint::Length(10),
+                ];
+                Clear.render(area, buf);
+                Widget::render(
+                    Table::new(
+                        rows.into_iter()
+                            .map(|(name, size, percent)| Row::new([name.into(), size, percent])),
+                        widths,
+                    )
+                    .header(header)
+                    .block(Popup::block(" Memory ".into())),
+                    area,
+                    buf,
+                );
+            }
+            Popup::Swap(summary) => {
+                let text = swap_text(summary);
+                let area = Popup::area(area, 72, text.height() as u16 + 2);
+                Clear.render(area, buf);
+                Paragraph::new(text)
+                    .block(Popup::block(" Swap ".into()))
+                    .render(area, buf);
+            }
+        }
+    }
+}
+
+/// PSIのavg10/60/300を19桁に揃える。`full`のない資源は`-`
+pub fn pressure_avgs(avg: Option<&PressureAvg>) -> String {
+    match avg {
+        Some(avg) => format!("{:>6.2}{:>6.2}{:>7.2}", avg.avg10, avg.avg60, avg.avg300),
+        None => format!("{:>19}", "-"),
+    }
+}
+
+/// スワップ先ごとの使用量と、zram/zswapの圧縮率
+fn swap_text(summary: &SwapSummary) -> Text<'static> {
+    let size = |bytes: u64| DisplayBytes::new(bytes).to_string();
+    let rate = |bytes: u64| {
+        let text = format!("{}/s", size(bytes));
+        // 出入りが続いているならスラッシングを疑う
+        if bytes > 0 {
+            text.red().bold()
+        } else {
+            text.green()
+        }
+    };
+    let ratio = |ratio: Option<f64>| {
+        ratio
+            .map(|ratio| format!("{ratio:.2}x"))
+            .unwrap_or_else(|| "-".into())
+    };
+    let header = |text: String| Line::from(text.bold().fg(tailwind::BLUE.c400));
+
+    let mut lines = vec![Line::from(vec![
+        "Swap In: ".bold(),
+        rate(summary.swap_in),
+        "  Swap Out: ".bold(),
+        rate(summary.swap_out),
+    ])];
+
+    lines.push(Line::default());
+    lines.push(header(format!(
+        "{:<28}{:<10}{:>10}{:>10}{:>6}",
+        "DEVICE", "TYPE", "SIZE", "USED", "PRIO"
+    )));
+    if summary.devices.is_empty() {
+        lines.push(Line::from("No swap devices".dark_gray()));
+    }
+    lines.extend(summary.devices.iter().map(|device| {
+        Line::from(format!(
+            "{:<28}{:<10}{:>10}{:>10}{:>6}",
+            device.name,
+            device.kind,
+            size(device.size),
+            size(device.used),
+            device.priority
+        ))
+    }));
+
+    // 初期化されていないzramは飛ばす
+    let zram = summary
+        .zram
+        .iter()
+        .filter(|device| device.disk_size > 0)
+        .collect::<Vec<_>>();
+    if !zram.is_empty() {
+        lines.push(Line::default());
+        lines.push(header(format!(
+            "{:<10}{:<10}{:>10}{:>10}{:>10}{:>10}{:>8}",
+            "ZRAM", "ALGO", "DISKSIZE", "DATA", "COMPR", "TOTAL", "RATIO"
+        )));
+        lines.extend(zram.into_iter().map(|device| {
+            Line::from(format!(
+                "{:<10}{:<10}{:>10}{:>10}{:>10}{:>10}{:>8}",
+                device.name,
+                device.algorithm.as_deref().unwrap_or("-"),
+                size(device.disk_size),
+                size(device.orig_data_size),
+                size(device.compr_data_size),
+                size(device.mem_used_total),
+                ratio(device.ratio())
+            ))
+        }));
+    }
+
+    if let Some(zswap) = &summary.zswap {
+        lines.push(Line::default());
+        lines.push(Line::from(vec![
+            "Zswap: ".bold(),
+            if zswap.enabled {
+                "enabled".green()
+            } else {
+                "disabled".dark_gray()
+            },
+            format!(
+                "  {}  pool {}  stored {}  ratio {}",
+                zswap.compressor.as_deref().unwrap_or("-"),
+                size(zswap.pool_size),
+                size(zswap.stored),
+                ratio(zswap.ratio())
+            )
+            .into(),
+        ]));
+    }
+    Text::from(lines)
+}
+
+fn detail_text(detail: &ProcessDetail) -> Text<'static> {
+    const UNAVAILABLE: &str = "(unavailable)";
+
+    fn section(lines: &mut Vec<Line<'static>>, title: &str) {
+        if !lines.is_empty() {
+            lines.push(Line::default());
+        }
+        lines.push(Line::from(title.to_owned().blue().bold()));
+    }
+    fn field(key: &str, value: String) -> Line<'static> {
+        Line::from(vec![format!("{key}: ").bold(), value.green()])
+    }
+    let path = |path: &Option<std::path::PathBuf>| {
+        path.as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| UNAVAILABLE.into())
+    };
+
+    let mut lines = vec![];
+    section(&mut lines, "Process");
+    lines.push(field("Executable", path(&detail.exe)));
+    lines.push(field("Working Directory", path(&detail.cwd)));
+    lines.push(field(
+        "Threads",
+        detail
+            .get_threads()
+            .map(|threads| threads.to_string())
+            .unwrap_or_else(|| UNAVAILABLE.into()),
+    ));
+    lines.push(field(
+        "Command Line",
+        detail
+            .cmdline
+            .as_ref()
+            .map(|cmd| cmd.join(" "))
+            .unwrap_or_else(|| UNAVAILABLE.into()),
+    ));
+
+    section(&mut lines, "Status");
+    match &detail.status {
+        Some(status) => lines.extend(status.iter().map(|(k, v)| field(k, v.clone()))),
+        None => lines.push(Line::from(UNAVAILABLE)),
+    }
+
+    section(&mut lines, "Resource Limits");
+    match &detail.limits {
+        Some(limits) => lines.extend(limits.iter().map(|limit| {
+            Line::from(format!(
+                "{:<26}{:>14}{:>14} {}",
+                limit.name, limit.soft, limit.hard, limit.units
+            ))
+        })),
+        None => lines.push(Line::from(UNAVAILABLE)),
+    }
+
+    match &detail.open_files {
+        Some(files) => {
+            section(&mut lines, &format!("Open Files ({})", files.len()));
+            lines.extend(
+                files
+                    .iter()
+                    .map(|file| Line::from(format!("{:>5} {}", file.fd, file.target))),
+            );
+        }
+        None => {
+            section(&mut lines, "Open Files");
+            lines.push(Line::from(UNAVAILABLE));
+        }
+    }
+
+    match &detail.sockets {
+        Some(sockets) => {
+            section(&mut lines, &format!("Sockets ({})", sockets.len()));
+            lines.extend(sockets.iter().map(|socket| {
+                Line::from(format!(
+                    "{:<5} {:<10} {:<24} {}",
+                    socket.protocol.to_string(),
+                    socket.state,
+                    socket.local,
+                    socket.remote
+                ))
+            }));
+        }
+        None => {
+            section(&mut lines, "Sockets");
+            lines.push(Line::from(UNAVAILABLE));
+        }
+    }
+
+    section(&mut lines, "Cgroup Pressure");
+    match &detail.pressure {
+        Some(pressure) => {
+            lines.push(Line::from(format!(
+                "{:<7}{:>19}{:>21}",
+                "", "some 10/60/300", "full 10/60/300"
+            )));
+            lines.extend(PressureResource::iter().map(|resource| {
+                let value = pressure.get(resource);
+                Line::from(format!(
+                    "{:<7}{}  {}",
+                    resource.to_string(),
+                    pressure_avgs(Some(&value.some)),
+                    pressure_avgs(value.full.as_ref())
+                ))
+            }));
+        }
+        None => lines.push(Line::from(UNAVAILABLE)),
+    }
+
+    section(&mut lines, "Memory Maps");
+    match &detail.maps {
+        Some(maps) => {
+            lines.push(field("Mappings", maps.mappings.to_string()));
+            lines.push(field("Total", DisplayBytes::new(maps.total_size).to_string()));
+            lines.push(field("File Backed", DisplayBytes::new(maps.file_size).to_string()));
+            lines.push(field("Anonymous", DisplayBytes::new(maps.anon_size).to_string()));
+            lines.extend(maps.largest_files.iter().map(|(path, size)| {
+                Line::from(format!("{:>8} {path}", DisplayBytes::new(*size).to_string()))
+            }));
+        }
+        None => lines.push(Line::from(UNAVAILABLE)),
+    }
+
+    section(&mut lines, "Environment");
+    match &detail.environ {
+        Some(environ) => lines.extend(environ.iter().map(|env| Line::from(env.clone()))),
+        None => lines.push(Line::from(UNAVAILABLE)),
+    }
+
+    Text::from(lines)
+}
+
+/// 画面下部に出す操作結果
+#[derive(Debug)]
+pub enum StatusMessage {
+    Info(String),
+    Error(String),
+}
+
+impl StatusMessage {
+    pub fn line(&self) -> Line<'_> {
+        match self {
+            StatusMessage::Info(message) => Line::from(message.as_str().green()),
+            StatusMessage::Error(message) => Line::from(message.as_str().red().bold()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use shared::error::AppResult;
+
+    use ratatui::{
+        buffer::Buffer,
+        layout::Rect,
+        style::palette::tailwind,
+        widgets::{StatefulWidgetRef, TableState, Widget},
+    };
+    use sysinfo::{Pid, ProcessStatus};
+
+    use std::collections::HashMap;
+
+    use crate::system::process::{
+        aggregate::GroupBy, memory::MemoryBreakdown, ProcessColumn, ProcessRow, SortOrder,
+    };
+
+    use crate::system::{
+        disk::{DiskInfo, DiskType},
+        frequency::CoreFrequency,
+        sensor::{SensorKind, SensorReading},
+        stat::CpuBreakdown,
+    };
+
+    use super::{
+        CpuBreakdownBar, CpuFrequencyGrid, CpuGrid, DiskGauges, ProcessTab, ProcessViewMode,
+        SensorTab,
+    };
+
+    fn row(pid: u32, cpu_usage: f32) -> ProcessRow {
+        ProcessRow {
+            pid: Pid::from_u32(pid),
+            name: format!("proc{pid}"),
+            user: "root".into(),
+            status: ProcessStatus::Run,
+            cpu_usage,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_set_rows_keeps_selection() -> AppResult<()> {
+        let mut tab = ProcessTab::default();
+        let mut state = TableState::default();
+        tab.sort.column = ProcessColumn::Cpu;
+        tab.sort.order = SortOrder::Descending;
+
+        tab.set_rows(vec![row(1, 1.), row(2, 2.), row(3, 3.)], &mut state);
+        state.select(Some(2));
+        assert_eq!(Pid::from_u32(1), tab.selected(&state).unwrap().pid);
+
+        tab.set_rows(vec![row(1, 9.), row(2, 2.), row(3, 3.)], &mut state);
+        assert_eq!(Some(0), state.selected());
+        assert_eq!(Pid::from_u32(1), tab.selected(&state).unwrap().pid);
+
+        tab.filter.push('3');
+        tab.set_rows(vec![row(1, 9.), row(2, 2.), row(3, 3.)], &mut state);
+        assert_eq!(1, tab.rows.len());
+        assert_eq!(3, tab.total);
+        assert_eq!(Some(0), state.selected());
+        Ok(())
+    }
+
+    #[test]
+    fn test_toggle_collapsed() -> AppResult<()> {
+        let mut tab = ProcessTab::default();
+        let mut state = TableState::default();
+        let mut child = row(2, 1.);
+        child.ppid = Some(Pid::from_u32(1));
+        let rows = vec![row(1, 1.), child];
+
+        tab.toggle_mode();
+        assert_eq!(ProcessViewMode::Tree, tab.mode);
+        tab.set_rows(rows.clone(), &mut state);
+        assert_eq!(2, tab.rows.len());
+
+        state.select(Some(0));
+        tab.toggle_collapsed(&state);
+        tab.set_rows(rows.clone(), &mut state);
+        assert_eq!(1, tab.rows.len());
+        assert_eq!(2., tab.rows[0].cpu_usage);
+
+        tab.toggle_collapsed(&state);
+        tab.set_rows(rows, &mut state);
+        assert_eq!(2, tab.rows.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cycle_group() -> AppResult<()> {
+        let mut tab = ProcessTab::default();
+        let mut state = TableState::default();
+        let mut rows = vec![row(1, 1.), row(2, 2.), row(3, 3.)];
+        rows[2].user = "alice".into();
+
+        tab.cycle_group();
+        assert_eq!(ProcessViewMode::Group(GroupBy::User), tab.mode);
+        tab.set_rows(rows.clone(), &mut state);
+        assert!(tab.rows.is_empty());
+        assert_eq!(2, tab.len());
+        assert!(tab.selected(&state).is_none());
+
+        tab.cycle_group();
+        tab.set_rows(rows.clone(), &mut state);
+        assert_eq!(ProcessViewMode::Group(GroupBy::Command), tab.mode);
+        assert_eq!(3, tab.len());
+
+        tab.cycle_group();
+        tab.set_rows(rows.clone(), &mut state);
+        assert_eq!(ProcessViewMode::Group(GroupBy::Cgroup), tab.mode);
+        assert_eq!(1, tab.len());
+
+        tab.cycle_group();
+        tab.set_rows(rows, &mut state);
+        assert_eq!(ProcessViewMode::Flat, tab.mode);
+        assert_eq!(3, tab.rows.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_memory_columns() -> AppResult<()> {
+        let mut tab = ProcessTab::default();
+        let mut state = TableState::default();
+        tab.sort.column = ProcessColumn::Rss;
+        tab.next_sort_column();
+        assert_eq!(ProcessColumn::Virt, tab.sort.column);
+
+        tab.toggle_memory();
+        tab.sort.column = ProcessColumn::Rss;
+        tab.next_sort_column();
+        assert!(tab.sorts_by_memory());
+        tab.toggle_memory();
+        assert!(!tab.sorts_by_memory());
+
+        tab.set_page_height(5);
+        tab.set_rows((1..=10).map(|pid| row(pid, 0.)).collect(), &mut state);
+        state.select(Some(8));
+        let rows = tab.visible_rows(&state);
+        assert_eq!(
+            vec![1, 2, 9],
+            rows.iter().map(|row| row.pid.as_u32()).collect::<Vec<_>>()
+        );
+
+        let breakdown = MemoryBreakdown {
+            pss: 10,
+            ..Default::default()
+        };
+        tab.set_memory(HashMap::from([(Pid::from_u32(9), breakdown)]));
+        assert_eq!("10B", ProcessColumn::Pss.cell(&tab.rows[8]));
+        assert_eq!("-", ProcessColumn::Pss.cell(&tab.rows[0]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cpu_grid() -> AppResult<()> {
+        let lines = |buf: &Buffer| {
+            (0..buf.area.height)
+                .map(|y| {
+                    (0..buf.area.width)
+                        .map(|x| buf[(x, y)].symbol())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // 少ないコアは1列の棒グラフ
+        let mut buf = Buffer::empty(Rect::new(0, 0, 20, 4));
+        CpuGrid::new(&[0., 50., 100., 25.]).render(buf.area, &mut buf);
+        assert_eq!("0 ░░░░░░░░░░░░   0% ", lines(&buf)[0]);
+        assert_eq!("2 ████████████ 100% ", lines(&buf)[2]);
+
+        // 高さが足りなければ列を増やす
+        let mut buf = Buffer::empty(Rect::new(0, 0, 40, 2));
+        CpuGrid::new(&[100.; 4]).render(buf.area, &mut buf);
+        assert!(lines(&buf)[1].starts_with("1 "));
+        assert!(lines(&buf)[1].chars().skip(20).collect::<String>().starts_with("3 "));
+
+        // 多すぎる時は濃淡表示
+        let mut buf = Buffer::empty(Rect::new(0, 0, 40, 4));
+        CpuGrid::new(&[100.; 128]).render(buf.area, &mut buf);
+        let full = format!("{}{}", "█".repeat(32), " ".repeat(8));
+        assert_eq!(vec![full.clone(), full.clone(), full.clone(), full], lines(&buf));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cpu_breakdown_bar() -> AppResult<()> {
+        let breakdown = CpuBreakdown {
+            user: 30.,
+            system: 30.,
+            iowait: 30.,
+            idle: 10.,
+            ..Default::default()
+        };
+        // 区切りごとに丸めても合計の幅は変わらない
+        let spans = CpuBreakdownBar::spans(&breakdown, 10);
+        assert_eq!(
+            vec!["███", "███", "███", "░"],
+            spans.iter().map(|span| span.content.as_ref()).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(tailwind::YELLOW.c400), spans[2].style.fg);
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 20, 4));
+        CpuGrid::new(&[90.])
+            .breakdowns(&[breakdown])
+            .render(buf.area, &mut buf);
+        assert_eq!(Some(tailwind::RED.c500), Some(buf[(7, 0)].fg));
+        assert_eq!(Some(tailwind::YELLOW.c400), Some(buf[(12, 0)].fg));
+        Ok(())
+    }
+
+    #[test]
+    fn test_disk_gauges() -> AppResult<()> {
+        let disk = |mount: &str, total, available| DiskInfo {
+            device: "/dev/sda1".into(),
+            mount_point: mount.into(),
+            file_system: "ext4".into(),
+            kind: DiskType::Ssd,
+            removable: mount.starts_with("/media"),
+            total,
+            available,
+        };
+        let disks = [
+            disk("/", 100 * 1024 * 1024 * 1024, 5 * 1024 * 1024 * 1024),
+            disk("/media/user/backup-2024", 512 * 1024 * 1024, 384 * 1024 * 1024),
+        ];
+        let mut buf = Buffer::empty(Rect::new(0, 0, 60, 2));
+        DiskGauges::new(&disks).render(buf.area, &mut buf);
+        let lines = (0..buf.area.height)
+            .map(|y| {
+                (0..buf.area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            "/                ext4 SSD   ████████████░  95.0G/100.0G  95%",
+            lines[0]
+        );
+        // GiB未満でも大きさが分かる
+        assert_eq!(
+            "…ser/backup-2024 ext4 SSD ⏏ ███░░░░░░░░░░ 128.0M/512.0M  25%",
+            lines[1]
+        );
+        assert_eq!(tailwind::RED.c500, buf[(28, 0)].fg);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cpu_frequency_grid() -> AppResult<()> {
+        let core = |core, throttles| CoreFrequency {
+            core,
+            current: 800,
+            min: Some(800),
+            max: Some(4200),
+            governor: Some("powersave".into()),
+            core_throttles: Some(throttles),
+            package_throttles: Some(3),
+        };
+        let cores = (0..4).map(|idx| core(idx, idx as u64)).collect::<Vec<_>>();
+        let mut buf = Buffer::empty(Rect::new(0, 0, 50, 3));
+        CpuFrequencyGrid::new(&cores).render(buf.area, &mut buf);
+        let lines = (0..buf.area.height)
+            .map(|y| {
+                (0..buf.area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            "Governor powersave  Throttled core 6 / package 3",
+            lines[0].trim_end()
+        );
+        // 2行ずつ縦に並べる
+        assert_eq!("0  800MHz 800-4200  2  800MHz 800-4200", lines[1].trim_end());
+        assert_eq!(tailwind::YELLOW.c400, buf[(9, 0)].fg);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sensor_tab() -> AppResult<()> {
+        let sensor = |value| SensorReading {
+            chip: "coretemp".into(),
+            label: "Core 0".into(),
+            kind: SensorKind::Temperature,
+            value,
+            limit: Some(80.),
+            critical: Some(100.),
+        };
+        let mut tab = SensorTab::default();
+        let mut state = TableState::default();
+        state.select(Some(5));
+        tab.set_sensors(vec![sensor(45.), sensor(101.)], &mut state);
+        assert_eq!(Some(1), state.selected());
+
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, 5));
+        tab.render_ref(buf.area, &mut buf, &mut state);
+        let row = |y| {
+            (0..buf.area.width)
+                .map(|x| buf[(x, y)].symbol())
+                .collect::<String>()
+        };
+        assert!(row(3).contains("101.0°C"));
+        let x = row(3).chars().position(|c| c == '°').unwrap_or_default() as u16;
+        assert_eq!(tailwind::RED.c500, buf[(x, 3)].fg);
+        assert_eq!(tailwind::GREEN.c500, buf[(x - 1, 2)].fg);
+
+        tab.set_sensors(vec![], &mut state);
+        assert_eq!(None, state.selected());
+        Ok(())
+    }
+
+    #[test]
+    fn test_visible_columns() -> AppResult<()> {
+        let narrow = ProcessTab::visible_columns(40, false);
+        assert_eq!(
+            vec![ProcessColumn::Pid, ProcessColumn::Cpu, ProcessColumn::Name],
+            narrow
+        );
+        // 狭くても名前は出す
+        for width in [30, 40, 60, 80] {
+            for show_memory in [false, true] {
+                let columns = ProcessTab::visible_columns(width, show_memory);
+                assert!(columns.contains(&ProcessColumn::Name), "{width} {show_memory}");
+            }
+        }
+        // メモリ列を出すと、入りきらないUser以降は出さない
+        assert_eq!(
+            vec![
+                ProcessColumn::Pid,
+                ProcessColumn::Cpu,
+                ProcessColumn::Rss,
+                ProcessColumn::Pss,
+                ProcessColumn::Uss,
+                ProcessColumn::Swap,
+                ProcessColumn::Name,
+            ],
+            ProcessTab::visible_columns(80, true)
         );
 
-        scroll_view.render(area, buf, state);
+        let wide = ProcessTab::visible_columns(200, false);
+        assert_eq!(15, wide.len());
+        let wide = ProcessTab::visible_columns(250, true);
+        assert_eq!(18, wide.len());
+        assert_eq!(ProcessColumn::Pss, wide[6]);
+        assert_eq!(Some(&ProcessColumn::Pid), wide.first());
+        Ok(())
     }
 }
@@ -9,8 +9,16 @@ pub enum AppError {
     IoError(#[from] io::Error),
     #[error("This platform is not supported")]
     NoSupported,
+    #[error("{0} is not available on this system")]
+    NotAvailable(&'static str),
     #[error("{0}")]
     LogError(#[from] flexi_logger::FlexiLoggerError),
+    #[error("Failed to send {signal} to PID {pid}: {source}")]
+    SignalError {
+        pid: u32,
+        signal: String,
+        source: io::Error,
+    },
 }
 
 pub type AppResult<T> = Result<T,AppError>;
\ No newline at end of file
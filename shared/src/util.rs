@@ -1,4 +1,4 @@
-use std::{ffi::OsStr, fmt};
+use std::{ffi::OsStr, fmt, time::Duration};
 
 pub struct DisplayOsStr<'a>(&'a OsStr);
 
@@ -14,11 +14,68 @@ impl<'a> fmt::Display for DisplayOsStr<'a> {
     }
 }
 
+/// バイト数を`1.5G`のような読みやすい単位で表示する
+pub struct DisplayBytes(u64);
+
+impl DisplayBytes {
+    const UNITS: [&'static str; 6] = ["B", "K", "M", "G", "T", "P"];
+
+    pub fn new(bytes: u64) -> Self {
+        Self(bytes)
+    }
+}
+
+impl fmt::Display for DisplayBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut value = self.0 as f64;
+        let mut unit = 0;
+        while value >= 1024. && unit < Self::UNITS.len() - 1 {
+            value /= 1024.;
+            unit += 1;
+        }
+
+        if unit == 0 {
+            write!(f, "{}{}", self.0, Self::UNITS[unit])
+        } else {
+            write!(f, "{:.1}{}", value, Self::UNITS[unit])
+        }
+    }
+}
+
+/// 経過時間を`2m05s`のように上位2つの単位で表示する
+pub struct DisplayDuration(Duration);
+
+impl DisplayDuration {
+    pub fn new(duration: Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl fmt::Display for DisplayDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.0.as_secs();
+        let (days, hours, mins, secs) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+        if days > 0 {
+            write!(f, "{days}d{hours:02}h")
+        } else if hours > 0 {
+            write!(f, "{hours}h{mins:02}m")
+        } else if mins > 0 {
+            write!(f, "{mins}m{secs:02}s")
+        } else if secs > 0 {
+            write!(f, "{secs}s")
+        } else {
+            write!(f, "{}ms", self.0.as_millis())
+        }
+    }
+}
+
 #[cfg(test)]
 mod test{
     use std::ffi::OsStr;
 
-    use crate::{error::AppResult, util::DisplayOsStr};
+    use std::time::Duration;
+
+    use crate::{error::AppResult, util::{DisplayBytes, DisplayDuration, DisplayOsStr}};
 
     #[test]
     fn test_displayosstr()->AppResult<()>{
@@ -28,4 +85,25 @@ mod test{
 
         Ok(())
     }
+
+    #[test]
+    fn test_displaybytes()->AppResult<()>{
+        assert_eq!("512B", DisplayBytes::new(512).to_string());
+        assert_eq!("1.5K", DisplayBytes::new(1536).to_string());
+        assert_eq!("500.0M", DisplayBytes::new(500 * 1024 * 1024).to_string());
+        assert_eq!("2.0G", DisplayBytes::new(2 * 1024 * 1024 * 1024).to_string());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_displayduration()->AppResult<()>{
+        assert_eq!("250ms", DisplayDuration::new(Duration::from_millis(250)).to_string());
+        assert_eq!("42s", DisplayDuration::new(Duration::from_secs(42)).to_string());
+        assert_eq!("2m05s", DisplayDuration::new(Duration::from_secs(125)).to_string());
+        assert_eq!("1h02m", DisplayDuration::new(Duration::from_secs(3720)).to_string());
+        assert_eq!("3d04h", DisplayDuration::new(Duration::from_secs(3 * 86400 + 4 * 3600)).to_string());
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -2,7 +2,7 @@ use std::{io::stdout, thread};
 
 use api::app::{Application, Tui};
 use flexi_logger::{FileSpec, Logger, TS_DASHES_BLANK_COLONS_DOT_BLANK};
-use log::{error, info};
+use log::error;
 use ratatui::{
     crossterm::{
         terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
@@ -11,7 +11,7 @@ use ratatui::{
     prelude::CrosstermBackend,
     Terminal,
 };
-use shared::error::{AppError, AppResult};
+use shared::error::AppResult;
 
 #[tokio::main]
 async fn main() -> AppResult<()> {